@@ -0,0 +1,142 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::error::AppError;
+use crate::storage;
+
+const ARCHIVE_FILE: &str = "archive.txt";
+
+// yt-dlp appends to the file itself; this only serializes our own rewrites
+static ARCHIVE_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ArchiveEntry {
+    pub extractor: String,
+    pub id: String,
+}
+
+impl ArchiveEntry {
+    // Same key yt-dlp writes: lowercased extractor key and video id
+    pub fn key(extractor: &str, id: &str) -> String {
+        format!("{} {}", extractor.to_lowercase(), id)
+    }
+
+    fn parse(line: &str) -> Option<Self> {
+        let (extractor, id) = line.trim().split_once(' ')?;
+        if extractor.is_empty() || id.is_empty() {
+            return None;
+        }
+        Some(Self {
+            extractor: extractor.to_string(),
+            id: id.to_string(),
+        })
+    }
+
+    fn line(&self) -> String {
+        Self::key(&self.extractor, &self.id)
+    }
+}
+
+pub fn archive_path() -> Result<PathBuf, AppError> {
+    storage::data_file(ARCHIVE_FILE)
+}
+
+fn read_entries(path: &Path) -> Result<Vec<ArchiveEntry>, AppError> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(path)?;
+    Ok(content.lines().filter_map(ArchiveEntry::parse).collect())
+}
+
+fn write_entries(entries: &[ArchiveEntry]) -> Result<(), AppError> {
+    let path = archive_path()?;
+    let tmp_path = path.with_extension("txt.tmp");
+    let mut content = entries
+        .iter()
+        .map(|e| e.line())
+        .collect::<Vec<_>>()
+        .join("\n");
+    content.push('\n');
+    fs::write(&tmp_path, content)?;
+    fs::rename(&tmp_path, &path)?;
+    Ok(())
+}
+
+pub fn keys() -> HashSet<String> {
+    archive_path()
+        .and_then(|path| read_entries(&path))
+        .map(|entries| entries.iter().map(|e| e.line()).collect())
+        .unwrap_or_default()
+}
+
+pub fn contains(key: &str) -> bool {
+    keys().contains(key)
+}
+
+// Full extraction reports extractor_key, flat playlist entries only ie_key
+pub fn key_for(info: &serde_json::Value) -> Option<String> {
+    let extractor = info["extractor_key"]
+        .as_str()
+        .or_else(|| info["ie_key"].as_str())?;
+    let id = info["id"].as_str().filter(|id| !id.is_empty())?;
+    Some(ArchiveEntry::key(extractor, id))
+}
+
+pub fn is_skip_line(line: &str) -> bool {
+    line.contains("has already been recorded in the archive")
+}
+
+#[tauri::command]
+pub fn list_archive() -> Result<Vec<ArchiveEntry>, AppError> {
+    let _guard = ARCHIVE_LOCK.lock().unwrap();
+    read_entries(&archive_path()?)
+}
+
+#[tauri::command]
+pub fn remove_archive_entries(entries: Vec<ArchiveEntry>) -> Result<usize, AppError> {
+    let _guard = ARCHIVE_LOCK.lock().unwrap();
+    let mut current = read_entries(&archive_path()?)?;
+    let before = current.len();
+    current.retain(|e| !entries.contains(e));
+    write_entries(&current)?;
+    Ok(before - current.len())
+}
+
+#[tauri::command]
+pub fn clear_archive() -> Result<(), AppError> {
+    let _guard = ARCHIVE_LOCK.lock().unwrap();
+    write_entries(&[])
+}
+
+// Merges a yt-dlp archive file into ours, returning how many entries were new
+#[tauri::command]
+pub fn import_archive(path: String) -> Result<usize, AppError> {
+    let _guard = ARCHIVE_LOCK.lock().unwrap();
+    let imported = read_entries(Path::new(&path))?;
+    let mut current = read_entries(&archive_path()?)?;
+    let before = current.len();
+    for entry in imported {
+        if !current.contains(&entry) {
+            current.push(entry);
+        }
+    }
+    write_entries(&current)?;
+    Ok(current.len() - before)
+}
+
+#[tauri::command]
+pub fn export_archive(path: String) -> Result<(), AppError> {
+    let _guard = ARCHIVE_LOCK.lock().unwrap();
+    let entries = read_entries(&archive_path()?)?;
+    let mut content = entries
+        .iter()
+        .map(|e| e.line())
+        .collect::<Vec<_>>()
+        .join("\n");
+    content.push('\n');
+    fs::write(path, content)?;
+    Ok(())
+}
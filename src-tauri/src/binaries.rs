@@ -1,71 +1,303 @@
 use std::fs;
 #[cfg(unix)]
 use std::os::unix::fs::PermissionsExt;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use directories::ProjectDirs;
 use futures_util::StreamExt;
-use reqwest;
+use tauri::Emitter;
 use tokio::fs::File;
 use tokio::io::AsyncWriteExt;
 
-pub fn get_bin_dir() -> PathBuf {
-    let proj_dirs = ProjectDirs::from("com", "mevideo", "app").unwrap();
-    let bin_dir = proj_dirs.data_dir().join("bin");
+use crate::checksums;
+use crate::error::AppError;
+use crate::process;
+
+const EXTRACT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5 * 60);
+const PROGRESS_INTERVAL: std::time::Duration = std::time::Duration::from_millis(250);
+const YT_DLP_SUMS_URL: &str =
+    "https://github.com/yt-dlp/yt-dlp/releases/latest/download/SHA2-256SUMS";
+
+pub fn get_data_dir() -> Result<PathBuf, AppError> {
+    let proj_dirs = ProjectDirs::from("com", "mevideo", "app")
+        .ok_or_else(|| AppError::DataDir("no home directory".to_string()))?;
+    let data_dir = proj_dirs.data_dir().to_path_buf();
+    if !data_dir.exists() {
+        fs::create_dir_all(&data_dir).map_err(|e| AppError::DataDir(e.to_string()))?;
+    }
+    Ok(data_dir)
+}
+
+pub fn get_bin_dir() -> Result<PathBuf, AppError> {
+    let bin_dir = get_data_dir()?.join("bin");
     if !bin_dir.exists() {
-        fs::create_dir_all(&bin_dir).unwrap();
+        fs::create_dir_all(&bin_dir).map_err(|e| AppError::DataDir(e.to_string()))?;
     }
-    bin_dir
+    Ok(bin_dir)
 }
 
-pub fn check_binary_exists(name: &str) -> bool {
-    let bin_dir = get_bin_dir();
+pub fn bin_path(name: &str) -> Result<PathBuf, AppError> {
     let ext = if cfg!(windows) { ".exe" } else { "" };
-    let path = bin_dir.join(format!("{}{}", name, ext));
-    path.exists()
+    Ok(get_bin_dir()?.join(format!("{}{}", name, ext)))
 }
 
-pub async fn download_yt_dlp() -> Result<(), String> {
-    let bin_dir = get_bin_dir();
-    fs::create_dir_all(&bin_dir).map_err(|e| e.to_string())?;
-
-    let file_name = if cfg!(target_os = "windows") {
-        "yt-dlp.exe"
+// Path of the managed copy of a binary, or NotInstalled
+pub fn installed(name: &str) -> Result<PathBuf, AppError> {
+    let path = bin_path(name)?;
+    if path.exists() {
+        Ok(path)
     } else {
-        "yt-dlp"
-    };
-    let url = if cfg!(target_os = "windows") {
-        "https://github.com/yt-dlp/yt-dlp/releases/latest/download/yt-dlp.exe"
-    } else {
-        "https://github.com/yt-dlp/yt-dlp/releases/latest/download/yt-dlp"
-    };
+        Err(AppError::NotInstalled(name.to_string()))
+    }
+}
 
-    let response = reqwest::get(url).await.map_err(|e| e.to_string())?;
-    let mut file = File::create(bin_dir.join(file_name))
-        .await
-        .map_err(|e| e.to_string())?;
+#[derive(Clone, Copy, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstallPhase {
+    Download,
+    Verify,
+    Extract,
+    Chmod,
+}
+
+#[derive(Clone, serde::Serialize)]
+pub struct InstallProgress {
+    pub name: String,
+    pub phase: InstallPhase,
+    pub received: u64,
+    // Missing when the server sends no Content-Length
+    pub total: Option<u64>,
+    // Bytes per second
+    pub speed: Option<f64>,
+}
+
+fn emit_progress(
+    app: &tauri::AppHandle,
+    name: &str,
+    phase: InstallPhase,
+    received: u64,
+    total: Option<u64>,
+    speed: Option<f64>,
+) {
+    let _ = app.emit(
+        "binary-progress",
+        InstallProgress {
+            name: name.to_string(),
+            phase,
+            received,
+            total,
+            speed,
+        },
+    );
+}
+
+fn emit_phase(app: &tauri::AppHandle, name: &str, phase: InstallPhase) {
+    emit_progress(app, name, phase, 0, None, None);
+}
 
+// Streams `url` into `path`, hashing as it goes so the file is never read twice
+async fn download_to(
+    app: &tauri::AppHandle,
+    name: &str,
+    client: &reqwest::Client,
+    url: &str,
+    path: &Path,
+) -> Result<checksums::Hashes, AppError> {
+    let response = client.get(url).send().await?.error_for_status()?;
+    let total = response.content_length();
+    let mut file = File::create(path).await?;
+    let mut hasher = checksums::Hasher::default();
+
+    let started = std::time::Instant::now();
+    let mut last_emit: Option<std::time::Instant> = None;
+    let mut received = 0u64;
     let mut stream = response.bytes_stream();
     while let Some(item) = stream.next().await {
-        let chunk = item.map_err(|e| e.to_string())?;
-        file.write_all(&chunk).await.map_err(|e| e.to_string())?;
+        let chunk = item?;
+        hasher.update(&chunk);
+        file.write_all(&chunk).await?;
+        received += chunk.len() as u64;
+
+        // Chunks arrive every few KB; a few updates per second are enough for the UI
+        if last_emit.map_or(true, |at| at.elapsed() >= PROGRESS_INTERVAL) {
+            last_emit = Some(std::time::Instant::now());
+            let elapsed = started.elapsed().as_secs_f64();
+            let speed = (elapsed > 0.0).then(|| received as f64 / elapsed);
+            emit_progress(app, name, InstallPhase::Download, received, total, speed);
+        }
     }
+    file.flush().await?;
+    emit_progress(
+        app,
+        name,
+        InstallPhase::Download,
+        received,
+        total.or(Some(received)),
+        None,
+    );
+    Ok(hasher.finish())
+}
+
+fn verify_download(
+    name: &str,
+    expected: &checksums::Expected,
+    hashes: &checksums::Hashes,
+    path: &Path,
+) -> Result<(), AppError> {
+    let result = expected.check(name, hashes);
+    if result.is_err() {
+        let _ = fs::remove_file(path);
+    }
+    result
+}
+
+fn sibling_path(name: &str, suffix: &str) -> Result<PathBuf, AppError> {
+    let ext = if cfg!(windows) { ".exe" } else { "" };
+    Ok(get_bin_dir()?.join(format!("{}.{}{}", name, suffix, ext)))
+}
+
+// Where a download waits until it has been checked. It keeps the .exe extension
+// so Windows will still run it for validation.
+fn staged_path(name: &str) -> Result<PathBuf, AppError> {
+    sibling_path(name, "new")
+}
+
+fn prev_path(name: &str) -> Result<PathBuf, AppError> {
+    sibling_path(name, "prev")
+}
+
+fn version_arg(name: &str) -> &'static str {
+    if name == "yt-dlp" {
+        "--version"
+    } else {
+        "-version"
+    }
+}
+
+// Version reported by the executable at `path`, which need not be the managed copy
+pub async fn version(name: &str, path: &Path) -> Result<String, AppError> {
+    if name != "yt-dlp" && name != "ffmpeg" {
+        return Err(AppError::UnsupportedBinary(name.to_string()));
+    }
+
+    let mut cmd = process::command(path);
+    cmd.arg(version_arg(name));
+    let output = process::output(&mut cmd, name, process::VERSION_TIMEOUT).await?;
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    if name == "yt-dlp" {
+        return Ok(stdout.trim().to_string());
+    }
+
+    let first_line = stdout.lines().next().unwrap_or("Unknown");
+    // "ffmpeg version 7.0.1-essentials_build-www.gyan.dev ..." -> "7.0.1"
+    let raw_version = first_line
+        .replace("ffmpeg version ", "")
+        .split(' ')
+        .next()
+        .unwrap_or("Unknown")
+        .to_string();
+    let version = raw_version
+        .split('-')
+        .next()
+        .unwrap_or(&raw_version)
+        .to_string();
+    Ok(version)
+}
+
+// A binary that cannot print its version would break every download, so it never
+// replaces the working one
+async fn validate(name: &str, path: &Path) -> Result<(), AppError> {
+    let mut cmd = process::command(path);
+    cmd.arg(version_arg(name));
+    let output = process::output(&mut cmd, name, process::VERSION_TIMEOUT).await?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(AppError::process(
+            name,
+            format!(
+                "downloaded binary does not run: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        ))
+    }
+}
 
+// Moves a verified download into place, keeping the current binary as `<name>.prev`
+async fn install(
+    app: &tauri::AppHandle,
+    name: &str,
+    staged: &Path,
+    sha256: String,
+) -> Result<(), AppError> {
     #[cfg(unix)]
     {
-        let mut perms = fs::metadata(bin_dir.join(file_name))
-            .map_err(|e| e.to_string())?
-            .permissions();
+        emit_phase(app, name, InstallPhase::Chmod);
+        let mut perms = fs::metadata(staged)?.permissions();
         perms.set_mode(0o755);
-        fs::set_permissions(bin_dir.join(file_name), perms).map_err(|e| e.to_string())?;
+        fs::set_permissions(staged, perms)?;
     }
 
-    Ok(())
+    emit_phase(app, name, InstallPhase::Verify);
+    if let Err(e) = validate(name, staged).await {
+        let _ = fs::remove_file(staged);
+        return Err(e);
+    }
+
+    let target = bin_path(name)?;
+    let prev = prev_path(name)?;
+    let had_previous = target.exists();
+    if had_previous {
+        let _ = fs::remove_file(&prev);
+        fs::rename(&target, &prev)?;
+    }
+    if let Err(e) = fs::rename(staged, &target) {
+        if had_previous {
+            let _ = fs::rename(&prev, &target);
+        }
+        return Err(e.into());
+    }
+    checksums::rotate(name, sha256, had_previous)
 }
 
-pub async fn download_ffmpeg() -> Result<(), String> {
-    let bin_dir = get_bin_dir();
-    fs::create_dir_all(&bin_dir).map_err(|e| e.to_string())?;
+pub async fn download_yt_dlp(
+    app: &tauri::AppHandle,
+    client: &reqwest::Client,
+) -> Result<(), AppError> {
+    let url = if cfg!(target_os = "windows") {
+        "https://github.com/yt-dlp/yt-dlp/releases/latest/download/yt-dlp.exe"
+    } else {
+        "https://github.com/yt-dlp/yt-dlp/releases/latest/download/yt-dlp"
+    };
+    let remote_name = url.rsplit('/').next().unwrap_or("yt-dlp");
+
+    let expected = checksums::fetch_expected(client, YT_DLP_SUMS_URL, remote_name).await?;
+    // Nothing touches the working binary until the new one has been verified
+    let staged = staged_path("yt-dlp")?;
+    let hashes = download_to(app, "yt-dlp", client, url, &staged).await?;
+    emit_phase(app, "yt-dlp", InstallPhase::Verify);
+    verify_download(
+        "yt-dlp",
+        &checksums::Expected::Sha256(expected),
+        &hashes,
+        &staged,
+    )?;
+    install(app, "yt-dlp", &staged, hashes.sha256).await
+}
+
+async fn install_file(app: &tauri::AppHandle, name: &str, staged: &Path) -> Result<(), AppError> {
+    let path = staged.to_path_buf();
+    let sha256 = tauri::async_runtime::spawn_blocking(move || checksums::sha256_file(&path))
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))??;
+    install(app, name, staged, sha256).await
+}
+
+pub async fn download_ffmpeg(
+    app: &tauri::AppHandle,
+    client: &reqwest::Client,
+) -> Result<(), AppError> {
+    let bin_dir = get_bin_dir()?;
 
     let url = if cfg!(target_os = "windows") {
         "https://www.gyan.dev/ffmpeg/builds/ffmpeg-release-essentials.zip"
@@ -73,60 +305,143 @@ pub async fn download_ffmpeg() -> Result<(), String> {
         // Static build for Linux x86_64
         "https://johnvansickle.com/ffmpeg/releases/ffmpeg-release-amd64-static.tar.xz"
     };
-
-    let response = reqwest::get(url).await.map_err(|e| e.to_string())?;
     let fname = if cfg!(target_os = "windows") {
         "ffmpeg.zip"
     } else {
         "ffmpeg.tar.xz"
     };
+    let remote_name = url.rsplit('/').next().unwrap_or(fname);
+
+    // gyan.dev publishes a .sha256 next to each build, johnvansickle only an .md5
+    let expected = if cfg!(target_os = "windows") {
+        checksums::Expected::Sha256(
+            checksums::fetch_expected(client, &format!("{}.sha256", url), remote_name).await?,
+        )
+    } else {
+        checksums::Expected::Md5(
+            checksums::fetch_expected(client, &format!("{}.md5", url), remote_name).await?,
+        )
+    };
+
     let temp_file = bin_dir.join(fname);
-    let mut file = File::create(&temp_file).await.map_err(|e| e.to_string())?;
+    let hashes = download_to(app, "ffmpeg", client, url, &temp_file).await?;
+    emit_phase(app, "ffmpeg", InstallPhase::Verify);
+    verify_download("ffmpeg", &expected, &hashes, &temp_file)?;
 
-    let mut stream = response.bytes_stream();
-    while let Some(item) = stream.next().await {
-        let chunk = item.map_err(|e| e.to_string())?;
-        file.write_all(&chunk).await.map_err(|e| e.to_string())?;
-    }
+    emit_phase(app, "ffmpeg", InstallPhase::Extract);
+    let result = if cfg!(target_os = "windows") {
+        extract_zip(app, &temp_file).await
+    } else {
+        extract_tar(app, &temp_file, &bin_dir).await
+    };
+    let _ = fs::remove_file(temp_file);
+    result
+}
 
-    if cfg!(target_os = "windows") {
-        // Windows ZIP extraction logic (existing)
-        let file = std::fs::File::open(&temp_file).map_err(|e| e.to_string())?;
-        let mut archive = zip::ZipArchive::new(file).map_err(|e| e.to_string())?;
+async fn extract_zip(app: &tauri::AppHandle, archive_path: &Path) -> Result<(), AppError> {
+    let staged = staged_path("ffmpeg")?;
+    {
+        let file = std::fs::File::open(archive_path)?;
+        let mut archive = zip::ZipArchive::new(file)?;
 
         for i in 0..archive.len() {
-            let mut file = archive.by_index(i).map_err(|e| e.to_string())?;
+            let mut file = archive.by_index(i)?;
             if file.name().ends_with("ffmpeg.exe") {
-                let outpath = bin_dir.join("ffmpeg.exe");
-                let mut outfile = std::fs::File::create(&outpath).map_err(|e| e.to_string())?;
-                std::io::copy(&mut file, &mut outfile).map_err(|e| e.to_string())?;
+                let mut outfile = std::fs::File::create(&staged)?;
+                std::io::copy(&mut file, &mut outfile)?;
                 break;
             }
         }
-    } else {
-        // Linux TAR.XZ extraction
-        // For simplicity and to avoid many dependencies, we can use a command or a specialized crate
-        // johnvansickle tarballs usually have a subfolder. Using 'tar' command is often safest on Linux.
-        let output = std::process::Command::new("tar")
-            .arg("-xf")
-            .arg(&temp_file)
+    }
+    if !staged.exists() {
+        return Err(AppError::Parse(
+            "ffmpeg.exe not found in archive".to_string(),
+        ));
+    }
+    install_file(app, "ffmpeg", &staged).await
+}
+
+// johnvansickle tarballs have a versioned top folder holding ffmpeg and ffprobe;
+// yt-dlp looks for ffprobe next to ffmpeg, so both are installed
+async fn extract_tar(
+    app: &tauri::AppHandle,
+    archive_path: &Path,
+    bin_dir: &Path,
+) -> Result<(), AppError> {
+    let extract_dir = bin_dir.join("ffmpeg.extract");
+    let _ = fs::remove_dir_all(&extract_dir);
+    fs::create_dir_all(&extract_dir)?;
+
+    let result = async {
+        let mut cmd = process::command("tar");
+        cmd.arg("-xf")
+            .arg(archive_path)
             .arg("-C")
-            .arg(&bin_dir)
-            .arg("--strip-components=1")
-            .output()
-            .map_err(|e| format!("Failed to extract tar: {}", e))?;
+            .arg(&extract_dir)
+            .arg("--strip-components=1");
+        let output = process::output(&mut cmd, "tar", EXTRACT_TIMEOUT).await?;
 
         if !output.status.success() {
-            return Err(format!(
-                "Tar extraction failed: {}",
-                String::from_utf8_lossy(&output.stderr)
+            return Err(AppError::process(
+                "tar",
+                String::from_utf8_lossy(&output.stderr),
             ));
         }
 
-        // Ensure the binary is in the right place and called just 'ffmpeg'
-        // The strip-components might put 'ffmpeg' directly in bin_dir if chosen correctly
+        for name in ["ffmpeg", "ffprobe"] {
+            let extracted = extract_dir.join(name);
+            if !extracted.exists() {
+                if name == "ffmpeg" {
+                    return Err(AppError::Parse("ffmpeg not found in archive".to_string()));
+                }
+                continue;
+            }
+            let staged = staged_path(name)?;
+            fs::rename(&extracted, &staged)?;
+            install_file(app, name, &staged).await?;
+        }
+        Ok(())
+    }
+    .await;
+
+    let _ = fs::remove_dir_all(&extract_dir);
+    result
+}
+
+// Swaps the installed binary with the one it replaced, e.g. when a new yt-dlp
+// breaks an extractor. Running it again undoes the rollback.
+#[tauri::command]
+pub fn rollback_binary(name: String) -> Result<(), AppError> {
+    let companions: &[&str] = match name.as_str() {
+        "yt-dlp" => &["yt-dlp"],
+        "ffmpeg" => &["ffmpeg", "ffprobe"],
+        _ => return Err(AppError::UnsupportedBinary(name)),
+    };
+    if !prev_path(&name)?.exists() {
+        return Err(AppError::NotFound {
+            what: "previous version",
+            id: name,
+        });
     }
 
-    let _ = fs::remove_file(temp_file);
+    for tool in companions {
+        let target = bin_path(tool)?;
+        let prev = prev_path(tool)?;
+        if !prev.exists() {
+            continue;
+        }
+        let swap = sibling_path(tool, "swap")?;
+        if target.exists() {
+            fs::rename(&target, &swap)?;
+        }
+        if let Err(e) = fs::rename(&prev, &target) {
+            let _ = fs::rename(&swap, &target);
+            return Err(e.into());
+        }
+        if swap.exists() {
+            fs::rename(&swap, &prev)?;
+        }
+        checksums::swap(tool)?;
+    }
     Ok(())
 }
@@ -0,0 +1,219 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+
+use md5::Md5;
+use sha2::{Digest, Sha256};
+
+use crate::binaries;
+use crate::error::AppError;
+use crate::storage;
+
+// SHA-256 of each binary as installed, keyed by name
+const CHECKSUMS_FILE: &str = "bin/checksums.json";
+
+static CHECKSUMS_LOCK: Mutex<()> = Mutex::new(());
+
+// Published checksums come in different algorithms depending on the mirror
+pub enum Expected {
+    Sha256(String),
+    Md5(String),
+}
+
+#[derive(Default)]
+pub struct Hasher {
+    sha256: Sha256,
+    md5: Md5,
+}
+
+pub struct Hashes {
+    pub sha256: String,
+    pub md5: String,
+}
+
+impl Hasher {
+    pub fn update(&mut self, chunk: &[u8]) {
+        self.sha256.update(chunk);
+        self.md5.update(chunk);
+    }
+
+    pub fn finish(self) -> Hashes {
+        Hashes {
+            sha256: hex::encode(self.sha256.finalize()),
+            md5: hex::encode(self.md5.finalize()),
+        }
+    }
+}
+
+// Reads a checksum listing such as yt-dlp's SHA2-256SUMS ("<hash>  <file>" per line)
+// or a single-file `.md5`/`.sha256` that may hold only the hash
+fn parse_listing(text: &str, file_name: &str) -> Option<String> {
+    text.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        let hash = parts.next()?;
+        let matches = match parts.next() {
+            Some(name) => name.trim_start_matches('*') == file_name,
+            None => true,
+        };
+        (matches && hash.chars().all(|c| c.is_ascii_hexdigit())).then(|| hash.to_lowercase())
+    })
+}
+
+pub async fn fetch_expected(
+    client: &reqwest::Client,
+    url: &str,
+    file_name: &str,
+) -> Result<String, AppError> {
+    let text = client
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    parse_listing(&text, file_name)
+        .ok_or_else(|| AppError::Parse(format!("No checksum for {} in {}", file_name, url)))
+}
+
+impl Expected {
+    pub fn check(&self, name: &str, hashes: &Hashes) -> Result<(), AppError> {
+        let (expected, actual) = match self {
+            Expected::Sha256(h) => (h, &hashes.sha256),
+            Expected::Md5(h) => (h, &hashes.md5),
+        };
+        if expected.eq_ignore_ascii_case(actual) {
+            Ok(())
+        } else {
+            Err(AppError::ChecksumMismatch {
+                name: name.to_string(),
+                expected: expected.clone(),
+                actual: actual.clone(),
+            })
+        }
+    }
+}
+
+pub fn sha256_file(path: &Path) -> Result<String, AppError> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
+fn load_checksums() -> HashMap<String, String> {
+    storage::load_json(CHECKSUMS_FILE).unwrap_or_default()
+}
+
+fn prev_key(name: &str) -> String {
+    format!("{}.prev", name)
+}
+
+// Records a newly installed binary. The replaced binary's hash moves to
+// "<name>.prev" so a rollback can be verified too.
+pub fn rotate(name: &str, sha256: String, keep_previous: bool) -> Result<(), AppError> {
+    let _guard = CHECKSUMS_LOCK.lock().unwrap();
+    let mut checksums = load_checksums();
+    match checksums.insert(name.to_string(), sha256) {
+        Some(old) if keep_previous => checksums.insert(prev_key(name), old),
+        _ => checksums.remove(&prev_key(name)),
+    };
+    storage::save_json(CHECKSUMS_FILE, &checksums)
+}
+
+pub fn swap(name: &str) -> Result<(), AppError> {
+    let _guard = CHECKSUMS_LOCK.lock().unwrap();
+    let mut checksums = load_checksums();
+    let current = checksums.remove(name);
+    let prev = checksums.remove(&prev_key(name));
+    if let Some(hash) = prev {
+        checksums.insert(name.to_string(), hash);
+    }
+    if let Some(hash) = current {
+        checksums.insert(prev_key(name), hash);
+    }
+    storage::save_json(CHECKSUMS_FILE, &checksums)
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Integrity {
+    // Matches the hash recorded at install time
+    Verified,
+    Modified,
+    // Installed before checksums were recorded, or by hand
+    Unrecorded,
+}
+
+#[tauri::command]
+pub async fn verify_binary(name: String) -> Result<Integrity, AppError> {
+    let path = binaries::installed(&name)?;
+    let Some(recorded) = load_checksums().remove(&name) else {
+        return Ok(Integrity::Unrecorded);
+    };
+    // ffmpeg is large enough that hashing it would stall the runtime
+    let actual = tauri::async_runtime::spawn_blocking(move || sha256_file(&path))
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))??;
+    Ok(if actual == recorded {
+        Integrity::Verified
+    } else {
+        Integrity::Modified
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SUMS: &str = "\
+3b8c9a8f0e6f2f2f1c1e4d6a8b1f0a9d0c3e5b7a9f1d3c5e7a9b1d3f5a7c9e1b  yt-dlp
+0A1B2C3D4E5F60718293A4B5C6D7E8F90A1B2C3D4E5F60718293A4B5C6D7E8F9 *yt-dlp.exe
+ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff  yt-dlp_macos
+";
+
+    #[test]
+    fn finds_the_line_for_the_file() {
+        assert_eq!(
+            parse_listing(SUMS, "yt-dlp").as_deref(),
+            Some("3b8c9a8f0e6f2f2f1c1e4d6a8b1f0a9d0c3e5b7a9f1d3c5e7a9b1d3f5a7c9e1b")
+        );
+        // Binary-mode entries are prefixed with '*'; hashes are compared lowercased
+        assert_eq!(
+            parse_listing(SUMS, "yt-dlp.exe").as_deref(),
+            Some("0a1b2c3d4e5f60718293a4b5c6d7e8f90a1b2c3d4e5f60718293a4b5c6d7e8f9")
+        );
+        assert_eq!(parse_listing(SUMS, "yt-dlp_linux"), None);
+    }
+
+    #[test]
+    fn accepts_a_bare_hash() {
+        assert_eq!(
+            parse_listing("D41D8CD98F00B204E9800998ECF8427E\n", "ffmpeg.zip").as_deref(),
+            Some("d41d8cd98f00b204e9800998ecf8427e")
+        );
+        assert_eq!(parse_listing("", "ffmpeg.zip"), None);
+        assert_eq!(parse_listing("<html>Not Found</html>", "ffmpeg.zip"), None);
+    }
+
+    #[test]
+    fn hasher_matches_known_digests() {
+        let mut hasher = Hasher::default();
+        hasher.update(b"ab");
+        hasher.update(b"c");
+        let hashes = hasher.finish();
+        assert_eq!(
+            hashes.sha256,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(hashes.md5, "900150983cd24fb0d6963f7d28e17f72");
+        assert!(
+            Expected::Md5("900150983CD24FB0D6963F7D28E17F72".to_string())
+                .check("ffmpeg", &hashes)
+                .is_ok()
+        );
+        assert!(matches!(
+            Expected::Sha256("00".repeat(32)).check("ffmpeg", &hashes),
+            Err(AppError::ChecksumMismatch { .. })
+        ));
+    }
+}
@@ -0,0 +1,228 @@
+use std::collections::HashSet;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use tauri::Emitter;
+use tauri::Manager;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::download;
+use crate::download::DownloadOptions;
+use crate::error::AppError;
+use crate::net;
+use crate::playlist;
+use crate::process;
+use crate::queue;
+use crate::settings;
+use crate::tools;
+
+const POLL_MS: u64 = 1000;
+const MATCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct DownloadPreset {
+    pub format_ext: String,
+    pub format_height: u64,
+    pub custom_path: Option<String>,
+    pub options: DownloadOptions,
+}
+
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct ClipboardSettings {
+    pub enabled: bool,
+    // When empty, yt-dlp is asked whether one of its extractors handles the URL
+    pub patterns: Vec<String>,
+    // Queue detected URLs with the preset instead of asking the UI
+    pub auto_enqueue: bool,
+    pub preset: DownloadPreset,
+}
+
+pub fn validate(clipboard: &ClipboardSettings) -> Result<(), AppError> {
+    for p in &clipboard.patterns {
+        regex::Regex::new(p).map_err(|e| {
+            AppError::invalid(
+                "clipboard.patterns",
+                format!("Invalid clipboard pattern '{}': {}", p, e),
+            )
+        })?;
+    }
+    Ok(())
+}
+
+#[derive(Clone, serde::Serialize)]
+pub struct DetectedUrl {
+    pub url: String,
+}
+
+// yt-dlp reports these only when no extractor claims the URL; any other
+// failure (private video, 404, network) still means the URL is supported
+fn is_unsupported(stderr: &str) -> bool {
+    stderr.contains("Unsupported URL") || stderr.contains("no suitable InfoExtractor")
+}
+
+// Asks yt-dlp itself, so short links such as youtu.be or fb.watch match through the
+// extractors' own URL patterns and a yt-dlp update takes effect right away. The
+// generic extractor is left out since it accepts any web page. None when yt-dlp
+// could not give an answer.
+async fn matches_extractor(app: &tauri::AppHandle, url: &str) -> Option<bool> {
+    let yt_dlp_path = tools::path(app, "yt-dlp").ok()?;
+    let proxy = settings::current(app).proxy;
+    let mut cmd = process::command(&yt_dlp_path);
+    cmd.args(net::yt_dlp_proxy_args(&proxy, url).ok()?)
+        .arg("--ies")
+        .arg("default,-generic")
+        .arg("--simulate")
+        .arg("--flat-playlist")
+        .arg("--playlist-items")
+        .arg("1")
+        .arg("--no-warnings")
+        .arg(url);
+    let output = process::output(&mut cmd, "yt-dlp", MATCH_TIMEOUT)
+        .await
+        .ok()?;
+    Some(output.status.success() || !is_unsupported(&String::from_utf8_lossy(&output.stderr)))
+}
+
+async fn is_supported(
+    app: &tauri::AppHandle,
+    url: &str,
+    patterns: &[regex::Regex],
+) -> Option<bool> {
+    if patterns.is_empty() {
+        matches_extractor(app, url).await
+    } else {
+        Some(patterns.iter().any(|p| p.is_match(url)))
+    }
+}
+
+fn find_urls(text: &str) -> Vec<String> {
+    static URL_RE: OnceLock<regex::Regex> = OnceLock::new();
+    let re = URL_RE.get_or_init(|| regex::Regex::new(r#"https?://[^\s<>"']+"#).unwrap());
+    re.find_iter(text)
+        .map(|m| {
+            m.as_str()
+                .trim_end_matches(['.', ',', ')', ';'])
+                .to_string()
+        })
+        .collect()
+}
+
+fn handle_url(app: &tauri::AppHandle, url: String, settings: &ClipboardSettings) {
+    if !settings.auto_enqueue {
+        let _ = app.emit("url-detected", DetectedUrl { url });
+        return;
+    }
+
+    let preset = &settings.preset;
+    let result = download::resolve_dest(app, preset.custom_path.clone()).and_then(|dest| {
+        queue::enqueue(
+            app,
+            url.clone(),
+            playlist::shared_format_id(&preset.options, preset.format_height),
+            if preset.format_ext.is_empty() {
+                "mp4".to_string()
+            } else {
+                preset.format_ext.clone()
+            },
+            preset.format_height,
+            dest.to_string_lossy().to_string(),
+            preset.options.clone(),
+        )
+    });
+    match result {
+        Ok(_) => {
+            let _ = app.emit(
+                "download-log",
+                format!("📋 Adicionado da área de transferência: {}", url),
+            );
+            queue::pump(app);
+        }
+        Err(e) => {
+            let _ = app.emit("download-log", format!("Falha ao adicionar {}: {}", url, e));
+        }
+    }
+}
+
+// Polls the clipboard while the option is on. Text already on the clipboard when
+// watching starts is ignored, and each URL is only checked once per session.
+pub fn spawn_watcher(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_millis(POLL_MS));
+        let mut last_text: Option<String> = None;
+        let mut seen: HashSet<String> = HashSet::new();
+        loop {
+            interval.tick().await;
+            let settings = settings::current(&app).clipboard;
+            if !settings.enabled {
+                last_text = None;
+                continue;
+            }
+            let Ok(text) = app.clipboard().read_text() else {
+                continue;
+            };
+            let first_read = last_text.is_none();
+            if last_text.as_deref() == Some(text.as_str()) {
+                continue;
+            }
+            last_text = Some(text.clone());
+            if first_read {
+                continue;
+            }
+
+            let patterns: Vec<regex::Regex> = settings
+                .patterns
+                .iter()
+                .filter_map(|p| regex::Regex::new(p).ok())
+                .collect();
+            let queued: Vec<String> = app
+                .state::<queue::DownloadQueue>()
+                .jobs()
+                .into_iter()
+                .map(|j| j.url)
+                .collect();
+            for url in find_urls(&text) {
+                if seen.contains(&url) || queued.contains(&url) {
+                    continue;
+                }
+                let Some(supported) = is_supported(&app, &url, &patterns).await else {
+                    continue;
+                };
+                seen.insert(url.clone());
+                if supported {
+                    handle_url(&app, url, &settings);
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unsupported_only_when_no_extractor_claims_the_url() {
+        assert!(is_unsupported(
+            "ERROR: Unsupported URL: https://example.com/page"
+        ));
+        assert!(is_unsupported(
+            "ERROR: no suitable InfoExtractor for URL https://example.com/page"
+        ));
+        assert!(!is_unsupported(
+            "ERROR: [youtube] abc: Private video. Sign in if you've been granted access"
+        ));
+        assert!(!is_unsupported(
+            "ERROR: [twitter] 123: Unable to download JSON metadata: HTTP Error 404"
+        ));
+    }
+
+    #[test]
+    fn find_urls_trims_trailing_punctuation() {
+        assert_eq!(
+            find_urls("watch https://youtu.be/abc, or (https://x.com/a/status/1)."),
+            ["https://youtu.be/abc", "https://x.com/a/status/1"]
+        );
+    }
+}
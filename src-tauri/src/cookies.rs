@@ -0,0 +1,193 @@
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::binaries;
+use crate::error::AppError;
+use crate::storage;
+
+const PROFILES_FILE: &str = "cookie_profiles.json";
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct CookieProfile {
+    pub name: String,
+    // Sites this profile is applied to automatically, e.g. "youtube.com"
+    pub domains: Vec<String>,
+    pub cookie_count: usize,
+    pub imported_at: u64,
+}
+
+fn cookies_dir() -> Result<PathBuf, AppError> {
+    let dir = binaries::get_data_dir()?.join("cookies");
+    if !dir.exists() {
+        fs::create_dir_all(&dir)?;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&dir, fs::Permissions::from_mode(0o700))?;
+    }
+    Ok(dir)
+}
+
+fn profile_path(name: &str) -> Result<PathBuf, AppError> {
+    Ok(cookies_dir()?.join(format!("{}.txt", name)))
+}
+
+fn load_profiles() -> Vec<CookieProfile> {
+    storage::load_json(PROFILES_FILE).unwrap_or_default()
+}
+
+fn validate_name(name: &str) -> Result<(), AppError> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(AppError::invalid(
+            "name",
+            "Profile names may only contain letters, numbers, '-' and '_'",
+        ))
+    }
+}
+
+// Netscape format: domain, include-subdomains flag, path, secure, expiry, name, value.
+// Returns the domains found, without the leading dot used for subdomain cookies.
+fn parse_netscape(content: &str) -> Result<Vec<String>, AppError> {
+    let mut domains: Vec<String> = Vec::new();
+    let mut count = 0;
+    for line in content.lines() {
+        // curl marks HttpOnly cookies with this prefix instead of commenting them out
+        let line = line.strip_prefix("#HttpOnly_").unwrap_or(line);
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line.split('\t').collect();
+        if fields.len() != 7 {
+            return Err(AppError::invalid(
+                "source_path",
+                "Not a Netscape cookies.txt file",
+            ));
+        }
+        count += 1;
+        let domain = fields[0].trim_start_matches('.').to_lowercase();
+        if !domains.contains(&domain) {
+            domains.push(domain);
+        }
+    }
+    if count == 0 {
+        return Err(AppError::invalid(
+            "source_path",
+            "The cookies file has no cookies",
+        ));
+    }
+    Ok(domains)
+}
+
+pub fn write_private(path: &Path, content: &str) -> Result<(), AppError> {
+    let _ = fs::remove_file(path);
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+    file.write_all(content.as_bytes())?;
+    Ok(())
+}
+
+fn host_of(url: &str) -> Option<String> {
+    reqwest::Url::parse(url)
+        .ok()?
+        .host_str()
+        .map(|h| h.to_lowercase())
+}
+
+fn matches_domain(host: &str, domain: &str) -> bool {
+    host == domain || host.ends_with(&format!(".{}", domain))
+}
+
+// An explicit profile wins; otherwise the first profile whose domains cover the URL
+pub fn cookie_file_for(url: &str, profile: Option<&str>) -> Result<Option<PathBuf>, AppError> {
+    if let Some(name) = profile {
+        validate_name(name)?;
+        let path = profile_path(name)?;
+        if !path.exists() {
+            return Err(AppError::NotFound {
+                what: "cookie_profile",
+                id: name.to_string(),
+            });
+        }
+        return Ok(Some(path));
+    }
+
+    let Some(host) = host_of(url) else {
+        return Ok(None);
+    };
+    for p in load_profiles() {
+        if p.domains.iter().any(|d| matches_domain(&host, d)) {
+            let path = profile_path(&p.name)?;
+            if path.exists() {
+                return Ok(Some(path));
+            }
+        }
+    }
+    Ok(None)
+}
+
+#[tauri::command]
+pub fn import_cookies(
+    name: String,
+    source_path: String,
+    domains: Option<Vec<String>>,
+) -> Result<CookieProfile, AppError> {
+    validate_name(&name)?;
+    let content = fs::read_to_string(&source_path)?;
+    let found_domains = parse_netscape(&content)?;
+    let cookie_count = content
+        .lines()
+        .filter(|l| !l.trim().is_empty() && (!l.starts_with('#') || l.starts_with("#HttpOnly_")))
+        .count();
+
+    write_private(&profile_path(&name)?, &content)?;
+
+    let profile = CookieProfile {
+        name: name.clone(),
+        domains: domains
+            .filter(|d| !d.is_empty())
+            .map(|d| d.into_iter().map(|s| s.to_lowercase()).collect())
+            .unwrap_or(found_domains),
+        cookie_count,
+        imported_at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs(),
+    };
+
+    let mut profiles = load_profiles();
+    profiles.retain(|p| p.name != name);
+    profiles.push(profile.clone());
+    storage::save_json(PROFILES_FILE, &profiles)?;
+    Ok(profile)
+}
+
+#[tauri::command]
+pub fn list_cookie_profiles() -> Vec<CookieProfile> {
+    load_profiles()
+}
+
+#[tauri::command]
+pub fn delete_cookie_profile(name: String) -> Result<(), AppError> {
+    validate_name(&name)?;
+    let path = profile_path(&name)?;
+    if path.exists() {
+        fs::remove_file(path)?;
+    }
+    let mut profiles = load_profiles();
+    profiles.retain(|p| p.name != name);
+    storage::save_json(PROFILES_FILE, &profiles)
+}
@@ -0,0 +1,814 @@
+use std::path::PathBuf;
+
+use tauri::Emitter;
+use tauri::Manager;
+
+use crate::archive;
+use crate::cookies;
+use crate::error::AppError;
+use crate::failure;
+use crate::net;
+use crate::process;
+use crate::progress;
+use crate::queue;
+use crate::queue::Job;
+use crate::ratelimit;
+use crate::schedule::Schedule;
+use crate::settings;
+use crate::template;
+use crate::tools;
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DownloadMode {
+    #[default]
+    Video,
+    Audio,
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AudioFormat {
+    #[default]
+    Mp3,
+    M4a,
+    Opus,
+    Flac,
+    Wav,
+}
+
+impl AudioFormat {
+    fn as_str(&self) -> &'static str {
+        match self {
+            AudioFormat::Mp3 => "mp3",
+            AudioFormat::M4a => "m4a",
+            AudioFormat::Opus => "opus",
+            AudioFormat::Flac => "flac",
+            AudioFormat::Wav => "wav",
+        }
+    }
+
+    fn is_lossless(&self) -> bool {
+        matches!(self, AudioFormat::Flac | AudioFormat::Wav)
+    }
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SubtitleMode {
+    #[default]
+    None,
+    Sidecar,
+    Embed,
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SubtitleFormat {
+    #[default]
+    Srt,
+    Vtt,
+}
+
+impl SubtitleFormat {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SubtitleFormat::Srt => "srt",
+            SubtitleFormat::Vtt => "vtt",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct EmbedOptions {
+    pub thumbnail: bool,
+    pub metadata: bool,
+    pub chapters: bool,
+    pub info_json: bool,
+    pub description: bool,
+}
+
+// Seconds from the start of the video; no end means "until the end"
+#[derive(Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub struct TimeRange {
+    pub start: f64,
+    pub end: Option<f64>,
+}
+
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct DownloadOptions {
+    pub mode: DownloadMode,
+    pub audio_format: AudioFormat,
+    // In kbps; None keeps the best quality ffmpeg can produce
+    pub audio_bitrate: Option<u32>,
+    pub subtitle_mode: SubtitleMode,
+    pub subtitle_langs: Vec<String>,
+    pub subtitle_format: SubtitleFormat,
+    pub include_auto_subtitles: bool,
+    // None falls back to the defaults in settings
+    pub embed: Option<EmbedOptions>,
+    // Filled from settings at enqueue time so a resumed job writes to the same path
+    pub output_template: Option<String>,
+    pub restrict_filenames: Option<bool>,
+    pub windows_filenames: Option<bool>,
+    // None picks a profile by the URL's domain
+    pub cookie_profile: Option<String>,
+    // Bytes per second, on top of the global limit
+    pub rate_limit: Option<u64>,
+    pub sections: Vec<TimeRange>,
+    // Chapter titles, matched exactly
+    pub chapters: Vec<String>,
+    // Re-encode around the cut points instead of snapping to the nearest keyframe
+    pub precise_cuts: bool,
+    // Archive key ("extractor id") when known before download, e.g. from a playlist
+    pub archive_id: Option<String>,
+    // Download again even if the video is in the archive
+    pub ignore_archive: bool,
+    pub schedule: Option<Schedule>,
+}
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct OutputFile {
+    pub path: String,
+    // Only set for section/chapter downloads
+    pub clipped_duration: Option<f64>,
+}
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct DownloadResult {
+    pub path: String,
+    // The last file written
+    pub file: Option<String>,
+    // Every file the job produced; section downloads write one per section
+    #[serde(default)]
+    pub files: Vec<OutputFile>,
+    // The video was already in the download archive and nothing was written
+    pub skipped: bool,
+}
+
+// yt-dlp prints a progress line every chunk, so this much silence means the transfer hung
+pub const STALL_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(15 * 60);
+const PROBE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(60);
+
+#[derive(Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    fn of(line: &str) -> Self {
+        if line.starts_with("ERROR:") {
+            Severity::Error
+        } else if line.starts_with("WARNING:") {
+            Severity::Warning
+        } else {
+            Severity::Info
+        }
+    }
+}
+
+// A line yt-dlp wrote to stderr, sent as the `download-message` event
+#[derive(Clone, serde::Serialize)]
+pub struct DownloadMessage {
+    pub job_id: String,
+    pub severity: Severity,
+    pub message: String,
+}
+
+pub fn resolve_dest(
+    app: &tauri::AppHandle,
+    custom_path: Option<String>,
+) -> Result<PathBuf, AppError> {
+    if let Some(p) = custom_path {
+        Ok(PathBuf::from(p))
+    } else {
+        app.path()
+            .resolve("", tauri::path::BaseDirectory::Download)
+            .map_err(AppError::from)
+    }
+}
+
+// Build a robust format string using quality-based selection (always respected by yt-dlp)
+// with the original format_id as fallback.
+fn format_selector(job: &Job) -> (String, String) {
+    let video_sel = if job.format_height > 0 {
+        format!(
+            "bestvideo[height={}][ext={}]",
+            job.format_height, job.format_ext
+        )
+    } else {
+        job.format_id.clone()
+    };
+
+    let format_str = if job.format_ext == "mp4" {
+        format!(
+            "{v}+bestaudio[ext=m4a]/{v}+bestaudio/{f}+bestaudio[ext=m4a]/{f}+bestaudio/{v}/{f}/best",
+            v = video_sel,
+            f = job.format_id
+        )
+    } else if job.format_ext == "webm" {
+        format!(
+            "{v}+bestaudio[ext=webm]/{v}+bestaudio/{f}+bestaudio[ext=webm]/{f}+bestaudio/{v}/{f}/best",
+            v = video_sel,
+            f = job.format_id
+        )
+    } else {
+        format!(
+            "{v}+bestaudio/{f}+bestaudio/{v}/{f}/best",
+            v = video_sel,
+            f = job.format_id
+        )
+    };
+
+    (video_sel, format_str)
+}
+
+fn subtitle_args(cmd: &mut tokio::process::Command, job: &Job) {
+    let options = &job.options;
+    if options.subtitle_mode == SubtitleMode::None || options.subtitle_langs.is_empty() {
+        return;
+    }
+
+    cmd.arg("--write-subs");
+    if options.include_auto_subtitles {
+        cmd.arg("--write-auto-subs");
+    }
+    cmd.arg("--sub-langs").arg(options.subtitle_langs.join(","));
+
+    // Audio files cannot carry subtitle tracks, so those always get sidecars
+    if options.subtitle_mode == SubtitleMode::Embed && options.mode == DownloadMode::Video {
+        // WebM only accepts WebVTT; mp4/mkv take SRT and yt-dlp converts it to mov_text for mp4
+        let format = if job.format_ext == "webm" {
+            SubtitleFormat::Vtt
+        } else {
+            SubtitleFormat::Srt
+        };
+        cmd.arg("--convert-subs")
+            .arg(format.as_str())
+            .arg("--embed-subs");
+    } else {
+        let format = options.subtitle_format.as_str();
+        cmd.arg("--sub-format")
+            .arg(format!("{}/best", format))
+            .arg("--convert-subs")
+            .arg(format);
+    }
+}
+
+fn embed_args(cmd: &mut tokio::process::Command, job: &Job, embed: EmbedOptions) {
+    // yt-dlp cannot attach cover art to these containers
+    let supports_thumbnail = match job.options.mode {
+        DownloadMode::Video => job.format_ext != "webm",
+        DownloadMode::Audio => job.options.audio_format != AudioFormat::Wav,
+    };
+    if embed.thumbnail && supports_thumbnail {
+        // Most sites serve webp, which mp4/m4a players do not show as cover art
+        cmd.arg("--embed-thumbnail")
+            .arg("--convert-thumbnails")
+            .arg("jpg");
+    }
+    if embed.metadata {
+        cmd.arg("--embed-metadata");
+    }
+    if embed.chapters {
+        cmd.arg("--embed-chapters");
+    }
+    if embed.info_json {
+        cmd.arg("--write-info-json");
+    }
+    if embed.description {
+        cmd.arg("--write-description");
+    }
+}
+
+fn escape_regex(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if "\\.+*?()|[]{}^$#&-~".contains(c) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+// yt-dlp would silently download nothing (or everything) for a malformed range
+pub fn validate_sections(sections: &[TimeRange]) -> Result<(), AppError> {
+    for range in sections {
+        if !range.start.is_finite() || range.start < 0.0 {
+            return Err(AppError::invalid(
+                "sections",
+                format!("Invalid section start {}", range.start),
+            ));
+        }
+        if let Some(end) = range.end {
+            if !end.is_finite() || end <= range.start {
+                return Err(AppError::invalid(
+                    "sections",
+                    format!(
+                        "Section end {} must be after its start {}",
+                        end, range.start
+                    ),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn has_sections(job: &Job) -> bool {
+    !job.options.sections.is_empty() || !job.options.chapters.is_empty()
+}
+
+fn section_args(cmd: &mut tokio::process::Command, job: &Job) {
+    for range in &job.options.sections {
+        let end = match range.end {
+            Some(end) => end.to_string(),
+            None => "inf".to_string(),
+        };
+        cmd.arg("--download-sections")
+            .arg(format!("*{}-{}", range.start, end));
+    }
+    // Without the leading '*' yt-dlp reads the section as a chapter title regex
+    for chapter in &job.options.chapters {
+        cmd.arg("--download-sections")
+            .arg(format!("^{}$", escape_regex(chapter)));
+    }
+    if has_sections(job) && job.options.precise_cuts {
+        cmd.arg("--force-keyframes-at-cuts");
+    }
+}
+
+// ffmpeg prints "Duration: HH:MM:SS.ss" for its input even when given no output
+async fn probe_duration(ffmpeg_path: &std::path::Path, file: &str) -> Option<f64> {
+    let mut cmd = process::command(ffmpeg_path);
+    cmd.arg("-hide_banner").arg("-i").arg(file);
+    let output = process::output(&mut cmd, "ffmpeg", PROBE_TIMEOUT)
+        .await
+        .ok()?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let rest = stderr.split("Duration: ").nth(1)?;
+    let timestamp = rest.split(',').next()?.trim();
+    let mut seconds = 0.0;
+    for part in timestamp.split(':') {
+        seconds = seconds * 60.0 + part.parse::<f64>().ok()?;
+    }
+    Some(seconds)
+}
+
+// Resuming relies on yt-dlp finding the same partial files, so the template
+// must only depend on the job itself
+fn output_template(job: &Job) -> String {
+    let template = job
+        .options
+        .output_template
+        .as_deref()
+        .unwrap_or(template::DEFAULT_TEMPLATE);
+    if job.options.sections.len() + job.options.chapters.len() > 1 {
+        format!("{}/{}", job.dest, template::with_section_field(template))
+    } else {
+        format!("{}/{}", job.dest, template)
+    }
+}
+
+// Extracts the file yt-dlp is about to write from its progress output
+fn destination_from_line(line: &str) -> Option<String> {
+    if let Some(rest) = line.strip_prefix("[download] Destination: ") {
+        return Some(rest.trim().to_string());
+    }
+    if let Some(rest) = line.strip_prefix("[Merger] Merging formats into ") {
+        return Some(rest.trim().trim_matches('"').to_string());
+    }
+    if let Some(rest) = line.strip_prefix("[ExtractAudio] Destination: ") {
+        return Some(rest.trim().to_string());
+    }
+    None
+}
+
+// Removes everything a cancelled job left behind: the recorded destinations
+// (including per-format intermediates like title.f137.mp4) and their partial,
+// fragment and post-processor temp files
+pub fn remove_partial_files(files: &[String]) {
+    for file in files {
+        let path = PathBuf::from(file);
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(format!("{}.part", file));
+        let _ = std::fs::remove_file(format!("{}.ytdl", file));
+
+        // Post-processors write title.temp.mp4 next to title.mp4 before renaming it
+        if let (Some(stem), Some(ext)) = (path.file_stem(), path.extension()) {
+            let temp = format!("{}.temp.{}", stem.to_string_lossy(), ext.to_string_lossy());
+            let _ = std::fs::remove_file(path.with_file_name(temp));
+        }
+
+        // Fragmented downloads (HLS/DASH) leave one .part-FragN file per fragment
+        let (Some(dir), Some(name)) = (path.parent(), path.file_name()) else {
+            continue;
+        };
+        let frag_prefix = format!("{}.part-Frag", name.to_string_lossy());
+        if let Ok(entries) = std::fs::read_dir(dir) {
+            for entry in entries.flatten() {
+                if entry
+                    .file_name()
+                    .to_string_lossy()
+                    .starts_with(&frag_prefix)
+                {
+                    let _ = std::fs::remove_file(entry.path());
+                }
+            }
+        }
+    }
+}
+
+// A clip is not the whole video, so section downloads never read or write the archive
+fn uses_archive(app: &tauri::AppHandle, job: &Job) -> bool {
+    settings::current(app).use_download_archive && !job.options.ignore_archive && !has_sections(job)
+}
+
+// Runs yt-dlp for a queued job until the process exits
+pub async fn run(app: &tauri::AppHandle, job: &Job) -> Result<DownloadResult, AppError> {
+    let yt_dlp_path = tools::path(app, "yt-dlp")?;
+    // Plain video downloads work without ffmpeg as long as no merging is needed
+    let ffmpeg_path = tools::path(app, "ffmpeg").ok();
+
+    let use_archive = uses_archive(app, job);
+    if use_archive
+        && job
+            .options
+            .archive_id
+            .as_deref()
+            .is_some_and(archive::contains)
+    {
+        let _ = app.emit(
+            "download-log",
+            "⏭️ Já baixado anteriormente, ignorado".to_string(),
+        );
+        return Ok(DownloadResult {
+            path: job.dest.clone(),
+            file: None,
+            files: Vec::new(),
+            skipped: true,
+        });
+    }
+
+    let mut cmd = process::command(&yt_dlp_path);
+
+    match job.options.mode {
+        DownloadMode::Video => {
+            let (video_sel, format_str) = format_selector(job);
+            let _ = app.emit(
+                "download-log",
+                format!(
+                    "Baixando {}p {} — seletor: {}",
+                    job.format_height,
+                    job.format_ext.to_uppercase(),
+                    video_sel
+                ),
+            );
+            cmd.arg("-f")
+                .arg(&format_str)
+                .arg("--merge-output-format")
+                .arg(&job.format_ext);
+        }
+        DownloadMode::Audio => {
+            // Extraction and conversion are done by ffmpeg
+            if ffmpeg_path.is_none() {
+                return Err(AppError::NotInstalled("ffmpeg".to_string()));
+            }
+            let audio_format = job.options.audio_format;
+            let format_str = if job.format_id.is_empty() {
+                "bestaudio/best".to_string()
+            } else {
+                format!("{}/bestaudio/best", job.format_id)
+            };
+            let _ = app.emit(
+                "download-log",
+                format!("Baixando áudio {}", audio_format.as_str().to_uppercase()),
+            );
+            cmd.arg("-f")
+                .arg(&format_str)
+                .arg("-x")
+                .arg("--audio-format")
+                .arg(audio_format.as_str());
+            if !audio_format.is_lossless() {
+                let quality = match job.options.audio_bitrate {
+                    Some(kbps) => format!("{}K", kbps),
+                    None => "0".to_string(),
+                };
+                cmd.arg("--audio-quality").arg(quality);
+            }
+        }
+    }
+
+    if let Some(limit) = ratelimit::limit_for(app, job) {
+        cmd.arg("--limit-rate").arg(limit.to_string());
+    }
+
+    cmd.args(net::yt_dlp_proxy_args(
+        &settings::current(app).proxy,
+        &job.url,
+    )?);
+
+    if let Some(cookie_file) =
+        cookies::cookie_file_for(&job.url, job.options.cookie_profile.as_deref())?
+    {
+        cmd.arg("--cookies").arg(cookie_file);
+    }
+
+    if has_sections(job) {
+        // Sections are cut by ffmpeg while downloading
+        if ffmpeg_path.is_none() {
+            return Err(AppError::NotInstalled("ffmpeg".to_string()));
+        }
+        section_args(&mut cmd, job);
+    }
+
+    if use_archive {
+        cmd.arg("--download-archive").arg(archive::archive_path()?);
+    }
+
+    subtitle_args(&mut cmd, job);
+    let embed = job
+        .options
+        .embed
+        .unwrap_or_else(|| settings::current(app).embed);
+    embed_args(&mut cmd, job, embed);
+
+    if job.options.restrict_filenames.unwrap_or(false) {
+        cmd.arg("--restrict-filenames");
+    }
+    if job.options.windows_filenames.unwrap_or(false) {
+        cmd.arg("--windows-filenames");
+    }
+
+    if let Some(ffmpeg_path) = &ffmpeg_path {
+        cmd.arg("--ffmpeg-location").arg(ffmpeg_path);
+    }
+    // Piped output would otherwise use the console code page on Windows
+    cmd.arg("--encoding")
+        .arg("utf-8")
+        .arg("--continue")
+        .arg("--newline")
+        .arg("--progress-template")
+        .arg(progress::DOWNLOAD_TEMPLATE)
+        .arg("--progress-template")
+        .arg(progress::POSTPROCESS_TEMPLATE)
+        .arg("-o")
+        .arg(output_template(job))
+        .arg(&job.url)
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped());
+
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| AppError::process("yt-dlp", e.to_string()))?;
+    // None means yt-dlp already exited, which the wait below picks up
+    if let Some(pid) = child.id() {
+        if !queue::register_pid(app, &job.id, pid) {
+            // Paused or cancelled while yt-dlp was starting up
+            process::kill_tree(pid);
+        }
+    }
+    let mut stdout = process::lines(child.stdout.take().unwrap());
+    // Drained on its own task so a chatty stderr can never fill the pipe and stall yt-dlp
+    let stderr = process::lines(child.stderr.take().unwrap());
+    let stderr_app = app.clone();
+    let stderr_job_id = job.id.clone();
+    let stderr_reader = tauri::async_runtime::spawn(process::drain_tail(stderr, move |l| {
+        let l = failure::redact(&l);
+        let _ = stderr_app.emit(
+            "download-message",
+            DownloadMessage {
+                job_id: stderr_job_id.clone(),
+                severity: Severity::of(&l),
+                message: l.clone(),
+            },
+        );
+        l
+    }));
+
+    // yt-dlp reports progress for every chunk; the UI only needs a few updates per second
+    let mut last_emit: Option<(std::time::Instant, progress::Phase, String)> = None;
+    // Every destination yt-dlp announces, in order; intermediate format files are
+    // deleted after merging, so the ones still on disk at the end are the outputs
+    let mut announced: Vec<String> = Vec::new();
+    let mut skipped = false;
+
+    loop {
+        let l = match tokio::time::timeout(STALL_TIMEOUT, stdout.next_line()).await {
+            Ok(Ok(Some(l))) => l,
+            Ok(Ok(None)) => break,
+            // An unread pipe would block yt-dlp, so it is stopped rather than waited on
+            Ok(Err(e)) => {
+                if let Some(pid) = child.id() {
+                    process::kill_tree(pid);
+                }
+                let _ = child.wait().await;
+                return Err(AppError::process("yt-dlp", e.to_string()));
+            }
+            Err(_) => {
+                if let Some(pid) = child.id() {
+                    process::kill_tree(pid);
+                }
+                let _ = child.wait().await;
+                return Err(AppError::Download {
+                    kind: failure::ErrorKind::Network,
+                    message: format!(
+                        "Download stalled, no output from yt-dlp for {} minutes",
+                        STALL_TIMEOUT.as_secs() / 60
+                    ),
+                    stderr: stderr_reader.await.unwrap_or_default(),
+                });
+            }
+        };
+        if progress::is_progress_line(&l) {
+            if let Some(p) = progress::parse_line(&job.id, &l) {
+                let due = match &last_emit {
+                    Some((at, phase, status)) => {
+                        at.elapsed() >= std::time::Duration::from_millis(250)
+                            || *phase != p.phase
+                            || *status != p.status
+                    }
+                    None => true,
+                };
+                if due {
+                    last_emit = Some((std::time::Instant::now(), p.phase, p.status.clone()));
+                    let _ = app.emit("download-progress", p);
+                }
+            }
+            continue;
+        }
+        if archive::is_skip_line(&l) {
+            skipped = true;
+        }
+        if let Some(file) = destination_from_line(&l) {
+            if !announced.contains(&file) {
+                announced.push(file.clone());
+            }
+            queue::record_file(app, &job.id, file);
+        }
+        let _ = app.emit("download-log", l);
+    }
+
+    let status = child.wait().await?;
+    let stderr_lines = stderr_reader.await.unwrap_or_default();
+    if status.success() {
+        let message = if skipped {
+            "⏭️ Já baixado anteriormente, ignorado"
+        } else {
+            "✅ Download concluído!"
+        };
+        let _ = app.emit("download-log", message.to_string());
+        let mut files = Vec::new();
+        for path in announced {
+            if !std::path::Path::new(&path).exists() {
+                continue;
+            }
+            let clipped_duration = match &ffmpeg_path {
+                Some(ffmpeg_path) if has_sections(job) => probe_duration(ffmpeg_path, &path).await,
+                _ => None,
+            };
+            files.push(OutputFile {
+                path,
+                clipped_duration,
+            });
+        }
+        Ok(DownloadResult {
+            path: job.dest.clone(),
+            file: files.last().map(|f| f.path.clone()),
+            files,
+            skipped,
+        })
+    } else {
+        Err(failure::classify(&stderr_lines, status.code()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cancelled_downloads_leave_nothing_behind() {
+        let dir = tempfile::tempdir().unwrap();
+        let video = dir.path().join("clip.f137.mp4");
+        let merged = dir.path().join("clip.mp4");
+        let leftovers = [
+            "clip.f137.mp4",
+            "clip.f137.mp4.part",
+            "clip.f137.mp4.ytdl",
+            "clip.f137.mp4.part-Frag3",
+            "clip.mp4",
+            "clip.temp.mp4",
+        ];
+        for name in leftovers {
+            std::fs::write(dir.path().join(name), b"x").unwrap();
+        }
+        std::fs::write(dir.path().join("other.mp4"), b"x").unwrap();
+
+        remove_partial_files(&[
+            video.to_string_lossy().into_owned(),
+            merged.to_string_lossy().into_owned(),
+        ]);
+
+        let remaining: Vec<String> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(remaining, vec!["other.mp4".to_string()]);
+    }
+
+    fn job_with(sections: Vec<TimeRange>, chapters: Vec<String>) -> Job {
+        let mut job: Job = serde_json::from_value(serde_json::json!({
+            "id": "1",
+            "url": "https://example.com/watch",
+            "format_id": "best",
+            "format_ext": "mp4",
+            "format_height": 0,
+            "dest": "/tmp",
+            "status": "pending",
+            "created_at": 0,
+        }))
+        .unwrap();
+        job.options.sections = sections;
+        job.options.chapters = chapters;
+        job
+    }
+
+    fn args(job: &Job) -> Vec<String> {
+        let mut cmd = tokio::process::Command::new("yt-dlp");
+        section_args(&mut cmd, job);
+        cmd.as_std()
+            .get_args()
+            .map(|a| a.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn chapter_titles_are_matched_literally() {
+        assert_eq!(escape_regex("Intro (part 1)"), "Intro \\(part 1\\)");
+        assert_eq!(escape_regex("a.b*c?"), "a\\.b\\*c\\?");
+        assert_eq!(escape_regex("plain"), "plain");
+    }
+
+    #[test]
+    fn sections_become_download_section_args() {
+        let job = job_with(
+            vec![
+                TimeRange {
+                    start: 10.0,
+                    end: Some(20.5),
+                },
+                TimeRange {
+                    start: 30.0,
+                    end: None,
+                },
+            ],
+            vec!["Q&A [live]".to_string()],
+        );
+        assert_eq!(
+            args(&job),
+            vec![
+                "--download-sections",
+                "*10-20.5",
+                "--download-sections",
+                "*30-inf",
+                "--download-sections",
+                "^Q\\&A \\[live\\]$",
+            ]
+        );
+
+        let mut precise = job_with(vec![], vec!["Intro".to_string()]);
+        precise.options.precise_cuts = true;
+        assert_eq!(
+            args(&precise),
+            vec![
+                "--download-sections",
+                "^Intro$",
+                "--force-keyframes-at-cuts"
+            ]
+        );
+        assert!(args(&job_with(vec![], vec![])).is_empty());
+    }
+
+    #[test]
+    fn malformed_sections_are_rejected() {
+        let range = |start, end| TimeRange { start, end };
+        assert!(validate_sections(&[range(0.0, Some(5.0)), range(5.0, None)]).is_ok());
+        for bad in [
+            range(-1.0, None),
+            range(5.0, Some(5.0)),
+            range(10.0, Some(5.0)),
+            range(f64::NAN, None),
+            range(0.0, Some(f64::NAN)),
+            range(0.0, Some(f64::INFINITY)),
+        ] {
+            assert!(validate_sections(&[bad]).is_err());
+        }
+    }
+}
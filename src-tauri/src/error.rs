@@ -0,0 +1,201 @@
+use serde::ser::SerializeStruct;
+
+use crate::failure::ErrorKind;
+
+// Every command returns this. It reaches the frontend as
+// { code, message_key, message, details, cause }: `code` is stable and meant for
+// branching, `message_key` for looking up a translation, `message` is an English
+// fallback and `details` holds the values the translated message needs.
+#[derive(Debug, Clone)]
+pub enum AppError {
+    // A managed binary such as yt-dlp or ffmpeg is missing
+    NotInstalled(String),
+    UnsupportedBinary(String),
+    NotFound {
+        what: &'static str,
+        id: String,
+    },
+    InvalidInput {
+        field: &'static str,
+        reason: String,
+    },
+    // The request is valid but not in the current state, e.g. pausing a finished job
+    InvalidState(String),
+    // The per-user data folder could not be located or created
+    DataDir(String),
+    Io(String),
+    Network(String),
+    // An external program could not be started or exited with an error
+    Process {
+        program: String,
+        cause: String,
+    },
+    Parse(String),
+    // A downloaded binary does not match its published checksum
+    ChecksumMismatch {
+        name: String,
+        expected: String,
+        actual: String,
+    },
+    // `stderr` is the tail of yt-dlp's output, for bug reports
+    Download {
+        kind: ErrorKind,
+        message: String,
+        stderr: Vec<String>,
+    },
+    Cancelled,
+    Internal(String),
+}
+
+impl AppError {
+    pub fn invalid(field: &'static str, reason: impl Into<String>) -> Self {
+        AppError::InvalidInput {
+            field,
+            reason: reason.into(),
+        }
+    }
+
+    pub fn process(program: &str, cause: impl Into<String>) -> Self {
+        AppError::Process {
+            program: program.to_string(),
+            cause: cause.into(),
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::NotInstalled(_) => "not_installed",
+            AppError::UnsupportedBinary(_) => "unsupported_binary",
+            AppError::NotFound { .. } => "not_found",
+            AppError::InvalidInput { .. } => "invalid_input",
+            AppError::InvalidState(_) => "invalid_state",
+            AppError::DataDir(_) => "data_dir_unavailable",
+            AppError::Io(_) => "io",
+            AppError::Network(_) => "network",
+            AppError::Process { .. } => "process_failed",
+            AppError::Parse(_) => "parse",
+            AppError::ChecksumMismatch { .. } => "checksum_mismatch",
+            AppError::Download { .. } => "download_failed",
+            AppError::Cancelled => "cancelled",
+            AppError::Internal(_) => "internal",
+        }
+    }
+
+    pub fn download_kind(&self) -> Option<ErrorKind> {
+        match self {
+            AppError::Download { kind, .. } => Some(*kind),
+            _ => None,
+        }
+    }
+
+    fn message_key(&self) -> String {
+        match self {
+            AppError::Download { kind, .. } => format!("errors.download.{}", kind.as_str()),
+            _ => format!("errors.{}", self.code()),
+        }
+    }
+
+    fn details(&self) -> serde_json::Value {
+        match self {
+            AppError::NotInstalled(name) | AppError::UnsupportedBinary(name) => {
+                serde_json::json!({ "name": name })
+            }
+            AppError::NotFound { what, id } => serde_json::json!({ "what": what, "id": id }),
+            AppError::InvalidInput { field, reason } => {
+                serde_json::json!({ "field": field, "reason": reason })
+            }
+            AppError::Process { program, .. } => serde_json::json!({ "program": program }),
+            AppError::ChecksumMismatch {
+                name,
+                expected,
+                actual,
+            } => serde_json::json!({ "name": name, "expected": expected, "actual": actual }),
+            AppError::Download { kind, stderr, .. } => {
+                serde_json::json!({ "kind": kind, "stderr": stderr })
+            }
+            _ => serde_json::Value::Null,
+        }
+    }
+
+    fn cause(&self) -> Option<&str> {
+        match self {
+            AppError::DataDir(cause)
+            | AppError::Io(cause)
+            | AppError::Network(cause)
+            | AppError::Parse(cause)
+            | AppError::Internal(cause)
+            // The translated message is generic, so the reason still has to reach the user
+            | AppError::InvalidState(cause)
+            | AppError::Process { cause, .. } => Some(cause),
+            AppError::Download { message, .. } => Some(message),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for AppError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AppError::NotInstalled(name) => write!(f, "{} not installed", name),
+            AppError::UnsupportedBinary(name) => write!(f, "Binary not supported: {}", name),
+            AppError::NotFound { what, id } => write!(f, "{} not found: {}", what, id),
+            AppError::InvalidInput { reason, .. } => write!(f, "{}", reason),
+            AppError::InvalidState(reason) => write!(f, "{}", reason),
+            AppError::DataDir(cause) => write!(f, "Data folder unavailable: {}", cause),
+            AppError::Io(cause) => write!(f, "File error: {}", cause),
+            AppError::Network(cause) => write!(f, "Network error: {}", cause),
+            AppError::Process { program, cause } => write!(f, "{} failed: {}", program, cause),
+            AppError::Parse(cause) => write!(f, "Unexpected response: {}", cause),
+            AppError::ChecksumMismatch { name, .. } => {
+                write!(f, "Checksum mismatch for {}, refusing to install", name)
+            }
+            AppError::Download { message, .. } => write!(f, "{}", message),
+            AppError::Cancelled => write!(f, "Download cancelled"),
+            AppError::Internal(cause) => write!(f, "{}", cause),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl serde::Serialize for AppError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("AppError", 5)?;
+        s.serialize_field("code", self.code())?;
+        s.serialize_field("message_key", &self.message_key())?;
+        s.serialize_field("message", &self.to_string())?;
+        s.serialize_field("details", &self.details())?;
+        s.serialize_field("cause", &self.cause())?;
+        s.end()
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(e: std::io::Error) -> Self {
+        AppError::Io(e.to_string())
+    }
+}
+
+impl From<reqwest::Error> for AppError {
+    fn from(e: reqwest::Error) -> Self {
+        AppError::Network(e.to_string())
+    }
+}
+
+impl From<serde_json::Error> for AppError {
+    fn from(e: serde_json::Error) -> Self {
+        AppError::Parse(e.to_string())
+    }
+}
+
+impl From<tauri::Error> for AppError {
+    fn from(e: tauri::Error) -> Self {
+        AppError::Internal(e.to_string())
+    }
+}
+
+impl From<zip::result::ZipError> for AppError {
+    fn from(e: zip::result::ZipError) -> Self {
+        AppError::Io(e.to_string())
+    }
+}
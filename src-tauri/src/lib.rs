@@ -1,7 +1,29 @@
 use tauri::Emitter;
 use tauri::Manager;
 
+use error::AppError;
+
+mod archive;
 mod binaries;
+mod checksums;
+mod clipboard;
+mod cookies;
+mod download;
+mod error;
+mod failure;
+mod net;
+mod playlist;
+mod process;
+mod progress;
+mod queue;
+mod ratelimit;
+mod schedule;
+mod settings;
+mod storage;
+mod subscriptions;
+mod template;
+mod tools;
+mod updates;
 
 fn position_window_bottom_right(window: &tauri::WebviewWindow) {
     if let Ok(Some(monitor)) = window.primary_monitor() {
@@ -18,28 +40,31 @@ fn position_window_bottom_right(window: &tauri::WebviewWindow) {
     }
 }
 
-fn toggle_window(app: &tauri::AppHandle) -> Result<(), String> {
+fn toggle_window(app: &tauri::AppHandle) -> Result<(), AppError> {
     let window = app
         .get_webview_window("main")
-        .ok_or("Janela não encontrada")?;
+        .ok_or_else(|| AppError::NotFound {
+            what: "window",
+            id: "main".to_string(),
+        })?;
     if window.is_visible().unwrap_or(false) {
-        window.hide().map_err(|e| e.to_string())?;
+        window.hide()?;
     } else {
         position_window_bottom_right(&window);
-        window.show().map_err(|e| e.to_string())?;
-        window.set_focus().map_err(|e| e.to_string())?;
+        window.show()?;
+        window.set_focus()?;
     }
     Ok(())
 }
 
 #[tauri::command]
-fn get_bin_path() -> String {
-    binaries::get_bin_dir().to_string_lossy().to_string()
+fn get_bin_path() -> Result<String, AppError> {
+    Ok(binaries::get_bin_dir()?.to_string_lossy().to_string())
 }
 
 #[tauri::command]
-fn open_bin_dir() {
-    let path = binaries::get_bin_dir();
+fn open_bin_dir() -> Result<(), AppError> {
+    let path = binaries::get_bin_dir()?;
     #[cfg(target_os = "windows")]
     {
         let _ = std::process::Command::new("explorer").arg(path).spawn();
@@ -48,62 +73,52 @@ fn open_bin_dir() {
     {
         let _ = std::process::Command::new("xdg-open").arg(path).spawn();
     }
+    Ok(())
 }
 
-#[tauri::command]
-fn check_binary(name: String) -> bool {
-    binaries::check_binary_exists(&name)
+#[derive(serde::Serialize)]
+struct BinaryStatus {
+    exists: bool,
+    // Where the active executable comes from; None when it was not found
+    source: Option<tools::ToolSource>,
+    path: Option<String>,
 }
 
 #[tauri::command]
-async fn get_binary_version(name: String) -> Result<String, String> {
-    let bin_dir = binaries::get_bin_dir();
-    let bin_path = if cfg!(target_os = "windows") {
-        bin_dir.join(format!("{}.exe", name))
-    } else {
-        bin_dir.join(&name)
-    };
-
-    if !bin_path.exists() {
-        return Err("Not installed".to_string());
+fn check_binary(app: tauri::AppHandle, name: String) -> BinaryStatus {
+    match tools::resolve(&app, &name) {
+        Ok(tool) => BinaryStatus {
+            exists: true,
+            source: Some(tool.source),
+            path: Some(tool.path.to_string_lossy().to_string()),
+        },
+        Err(_) => BinaryStatus {
+            exists: false,
+            source: None,
+            path: None,
+        },
     }
+}
 
-    let mut cmd = std::process::Command::new(&bin_path);
-    #[cfg(target_os = "windows")]
-    {
-        use std::os::windows::process::CommandExt;
-        cmd.creation_flags(0x08000000);
-    }
+#[derive(serde::Serialize)]
+struct BinaryVersion {
+    version: String,
+    source: tools::ToolSource,
+    path: String,
+}
 
-    if name == "yt-dlp" {
-        cmd.arg("--version");
-        let output = cmd.output().map_err(|e| e.to_string())?;
-        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
-    } else if name == "ffmpeg" {
-        cmd.arg("-version");
-        let output = cmd.output().map_err(|e| e.to_string())?;
-        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-        let first_line = stdout.lines().next().unwrap_or("Unknown");
-        // "ffmpeg version 7.0.1-essentials_build-www.gyan.dev ..." -> "7.0.1"
-        let raw_version = first_line
-            .replace("ffmpeg version ", "")
-            .split(' ')
-            .next()
-            .unwrap_or("Unknown")
-            .to_string();
-        let version = raw_version
-            .split('-')
-            .next()
-            .unwrap_or(&raw_version)
-            .to_string();
-        Ok(version)
-    } else {
-        Err("Unsupported binary".to_string())
-    }
+#[tauri::command]
+async fn get_binary_version(app: tauri::AppHandle, name: String) -> Result<BinaryVersion, AppError> {
+    let tool = tools::resolve(&app, &name)?;
+    Ok(BinaryVersion {
+        version: binaries::version(&name, &tool.path).await?,
+        source: tool.source,
+        path: tool.path.to_string_lossy().to_string(),
+    })
 }
 
 #[tauri::command]
-async fn download_binary(app: tauri::AppHandle, name: String, lang: String) -> Result<(), String> {
+async fn download_binary(app: tauri::AppHandle, name: String, lang: String) -> Result<(), AppError> {
     let msg_start = if lang == "en" {
         "Starting process for:"
     } else if lang == "es" {
@@ -135,8 +150,10 @@ async fn download_binary(app: tauri::AppHandle, name: String, lang: String) -> R
 
     let _ = app.emit("download-log", format!("{} {}", msg_start, name));
 
+    let client = net::client(&app);
+
     if name == "yt-dlp" {
-        let result = binaries::download_yt_dlp().await;
+        let result = binaries::download_yt_dlp(&app, &client).await;
         match result {
             Ok(_) => {
                 let _ = app.emit("download-log", format!("yt-dlp {}", msg_success));
@@ -149,7 +166,7 @@ async fn download_binary(app: tauri::AppHandle, name: String, lang: String) -> R
         }
     } else if name == "ffmpeg" {
         let _ = app.emit("download-log", msg_ffmpeg.to_string());
-        let result = binaries::download_ffmpeg().await;
+        let result = binaries::download_ffmpeg(&app, &client).await;
         match result {
             Ok(_) => {
                 let _ = app.emit("download-log", msg_ffmpeg_success.to_string());
@@ -161,15 +178,27 @@ async fn download_binary(app: tauri::AppHandle, name: String, lang: String) -> R
             }
         }
     } else {
-        Err("Binary not supported".to_string())
+        Err(AppError::UnsupportedBinary(name))
     }
 }
 
 #[derive(serde::Serialize)]
 struct VideoInfo {
+    id: String,
     title: String,
     thumbnail: String,
+    // Extra fields used to preview output templates
+    uploader: Option<String>,
+    channel: Option<String>,
+    upload_date: Option<String>,
+    duration: Option<f64>,
+    extractor: Option<String>,
     formats: Vec<FormatInfo>,
+    audio_formats: Vec<AudioFormatInfo>,
+    subtitles: Vec<SubtitleTrack>,
+    // Passed back in the download options so the queue can skip archived videos
+    archive_id: Option<String>,
+    archived: bool,
 }
 
 #[derive(serde::Serialize)]
@@ -182,48 +211,104 @@ struct FormatInfo {
     vcodec: String,
 }
 
-#[tauri::command]
-async fn get_video_info(url: String) -> Result<VideoInfo, String> {
-    let bin_dir = binaries::get_bin_dir();
-    let yt_dlp_path = if cfg!(target_os = "windows") {
-        bin_dir.join("yt-dlp.exe")
-    } else {
-        bin_dir.join("yt-dlp")
+#[derive(serde::Serialize)]
+struct AudioFormatInfo {
+    format_id: String,
+    ext: String,
+    acodec: String,
+    abr: Option<f64>,
+    filesize: Option<u64>,
+}
+
+#[derive(serde::Serialize)]
+struct SubtitleTrack {
+    lang: String,
+    name: Option<String>,
+    auto: bool,
+    exts: Vec<String>,
+}
+
+// `subtitles` and `automatic_captions` map a language code to the available renditions
+fn parse_subtitles(map: &serde_json::Value, auto: bool) -> Vec<SubtitleTrack> {
+    let Some(map) = map.as_object() else {
+        return Vec::new();
     };
+    let mut tracks: Vec<SubtitleTrack> = map
+        .iter()
+        // YouTube lists live chat replays as a subtitle track
+        .filter(|(lang, _)| lang.as_str() != "live_chat")
+        .map(|(lang, renditions)| {
+            let renditions = renditions.as_array().map(|r| r.as_slice()).unwrap_or(&[]);
+            SubtitleTrack {
+                lang: lang.clone(),
+                name: renditions
+                    .iter()
+                    .find_map(|r| r["name"].as_str())
+                    .map(|s| s.to_string()),
+                auto,
+                exts: renditions
+                    .iter()
+                    .filter_map(|r| r["ext"].as_str())
+                    .map(|s| s.to_string())
+                    .collect(),
+            }
+        })
+        .collect();
+    tracks.sort_by(|a, b| a.lang.cmp(&b.lang));
+    tracks
+}
 
-    if !yt_dlp_path.exists() {
-        return Err("yt-dlp not installed".to_string());
-    }
+#[tauri::command]
+async fn get_video_info(
+    app: tauri::AppHandle,
+    url: String,
+    cookie_profile: Option<String>,
+) -> Result<VideoInfo, AppError> {
+    let yt_dlp_path = tools::path(&app, "yt-dlp")?;
 
-    let mut cmd = std::process::Command::new(&yt_dlp_path);
-    #[cfg(target_os = "windows")]
-    {
-        use std::os::windows::process::CommandExt;
-        cmd.creation_flags(0x08000000);
+    let mut cmd = process::command(&yt_dlp_path);
+
+    cmd.args(net::yt_dlp_proxy_args(&settings::current(&app).proxy, &url)?);
+
+    if let Some(cookie_file) = cookies::cookie_file_for(&url, cookie_profile.as_deref())? {
+        cmd.arg("--cookies").arg(cookie_file);
     }
 
-    let output = cmd
-        .arg("-j")
-        .arg("--no-playlist")
-        .arg(&url)
-        .output()
-        .map_err(|e| e.to_string())?;
+    cmd.arg("-j").arg("--no-playlist").arg(&url);
+    let output = process::output(&mut cmd, "yt-dlp", process::QUERY_TIMEOUT).await?;
 
     if !output.status.success() {
-        return Err(String::from_utf8_lossy(&output.stderr).to_string());
+        let stderr: Vec<String> = String::from_utf8_lossy(&output.stderr)
+            .lines()
+            .map(|l| l.to_string())
+            .collect();
+        return Err(failure::classify(&stderr, output.status.code()));
     }
 
-    let json: serde_json::Value =
-        serde_json::from_slice(&output.stdout).map_err(|e| e.to_string())?;
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
 
     let title = json["title"].as_str().unwrap_or("Unknown").to_string();
     let thumbnail = json["thumbnail"].as_str().unwrap_or("").to_string();
 
     let mut formats = Vec::new();
+    let mut audio_formats = Vec::new();
     if let Some(formats_array) = json["formats"].as_array() {
         for f in formats_array {
             let vcodec = f["vcodec"].as_str().unwrap_or("none");
             if vcodec == "none" {
+                let acodec = f["acodec"].as_str().unwrap_or("none");
+                // Storyboards and other image tracks have neither codec
+                if acodec != "none" {
+                    audio_formats.push(AudioFormatInfo {
+                        format_id: f["format_id"].as_str().unwrap_or("").to_string(),
+                        ext: f["ext"].as_str().unwrap_or("").to_string(),
+                        acodec: acodec.to_string(),
+                        abr: f["abr"].as_f64(),
+                        filesize: f["filesize"]
+                            .as_u64()
+                            .or_else(|| f["filesize_approx"].as_u64()),
+                    });
+                }
                 continue;
             }
 
@@ -252,10 +337,34 @@ async fn get_video_info(url: String) -> Result<VideoInfo, String> {
         })
     });
 
+    // Highest bitrate first
+    audio_formats.sort_by(|a, b| {
+        b.abr
+            .unwrap_or(0.0)
+            .partial_cmp(&a.abr.unwrap_or(0.0))
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    // Manual subtitles first; auto-generated ones are often machine translations
+    let mut subtitles = parse_subtitles(&json["subtitles"], false);
+    subtitles.extend(parse_subtitles(&json["automatic_captions"], true));
+
+    let archive_id = archive::key_for(&json);
+
     Ok(VideoInfo {
+        id: json["id"].as_str().unwrap_or("").to_string(),
         title,
         thumbnail,
+        uploader: json["uploader"].as_str().map(|s| s.to_string()),
+        channel: json["channel"].as_str().map(|s| s.to_string()),
+        upload_date: json["upload_date"].as_str().map(|s| s.to_string()),
+        duration: json["duration"].as_f64(),
+        extractor: json["extractor"].as_str().map(|s| s.to_string()),
         formats,
+        audio_formats,
+        subtitles,
+        archived: archive_id.as_deref().is_some_and(archive::contains),
+        archive_id,
     })
 }
 
@@ -267,141 +376,58 @@ async fn download_video(
     format_ext: String,
     format_height: u64,
     custom_path: Option<String>,
-) -> Result<String, String> {
-    let bin_dir = binaries::get_bin_dir();
-    let yt_dlp_path = if cfg!(target_os = "windows") {
-        bin_dir.join("yt-dlp.exe")
-    } else {
-        bin_dir.join("yt-dlp")
-    };
-    let ffmpeg_path = if cfg!(target_os = "windows") {
-        bin_dir.join("ffmpeg.exe")
-    } else {
-        bin_dir.join("ffmpeg")
-    };
-
-    if !yt_dlp_path.exists() {
-        return Err("yt-dlp not installed".to_string());
-    }
-
-    let dest_path = if let Some(p) = custom_path {
-        std::path::PathBuf::from(p)
-    } else {
-        app.path()
-            .resolve("", tauri::path::BaseDirectory::Download)
-            .map_err(|e| e.to_string())?
-    };
-
-    // Build a robust format string using quality-based selection (always respected by yt-dlp)
-    // with the original format_id as fallback.
-    let video_sel = if format_height > 0 {
-        format!("bestvideo[height={}][ext={}]", format_height, format_ext)
-    } else {
-        format_id.clone()
-    };
-
-    let format_str = if format_ext == "mp4" {
-        format!(
-            "{v}+bestaudio[ext=m4a]/{v}+bestaudio/{f}+bestaudio[ext=m4a]/{f}+bestaudio/{v}/{f}/best",
-            v = video_sel,
-            f = format_id
-        )
-    } else if format_ext == "webm" {
-        format!(
-            "{v}+bestaudio[ext=webm]/{v}+bestaudio/{f}+bestaudio[ext=webm]/{f}+bestaudio/{v}/{f}/best",
-            v = video_sel,
-            f = format_id
-        )
-    } else {
-        format!(
-            "{v}+bestaudio/{f}+bestaudio/{v}/{f}/best",
-            v = video_sel,
-            f = format_id
-        )
-    };
-
-    let _ = app.emit(
-        "download-log",
-        format!(
-            "Baixando {}p {} — seletor: {}",
-            format_height,
-            format_ext.to_uppercase(),
-            video_sel
-        ),
-    );
-
-    let mut cmd = std::process::Command::new(&yt_dlp_path);
-    #[cfg(target_os = "windows")]
-    {
-        use std::os::windows::process::CommandExt;
-        cmd.creation_flags(0x08000000);
-    }
-
-    cmd.arg("--ffmpeg-location")
-        .arg(&ffmpeg_path)
-        .arg("-f")
-        .arg(&format_str)
-        .arg("--merge-output-format")
-        .arg(&format_ext)
-        .arg("-o")
-        .arg(format!("{}/%(title)s.%(ext)s", dest_path.to_string_lossy()))
-        .arg(&url)
-        .stdout(std::process::Stdio::piped())
-        .stderr(std::process::Stdio::piped());
-
-    let mut child = cmd.spawn().map_err(|e| e.to_string())?;
-    let stdout = child.stdout.take().unwrap();
-    let reader = std::io::BufReader::new(stdout);
-
-    use std::io::BufRead;
-    for line in reader.lines() {
-        if let Ok(l) = line {
-            let _ = app.emit("download-log", l);
-        }
-    }
-
-    let status = child.wait().map_err(|e| e.to_string())?;
-    if status.success() {
-        let _ = app.emit("download-log", "✅ Download concluído!".to_string());
-        Ok(dest_path.to_string_lossy().to_string())
-    } else {
-        Err("Processo do yt-dlp falhou".to_string())
-    }
+    options: Option<download::DownloadOptions>,
+) -> Result<download::DownloadResult, AppError> {
+    tools::resolve(&app, "yt-dlp")?;
+
+    let dest_path = download::resolve_dest(&app, custom_path)?;
+    let job = queue::enqueue(
+        &app,
+        url,
+        format_id,
+        format_ext,
+        format_height,
+        dest_path.to_string_lossy().to_string(),
+        options.unwrap_or_default(),
+    )?;
+    let done = queue::wait_for(&app, &job.id);
+    queue::pump(&app);
+    done.await.map_err(|_| AppError::Cancelled)?
 }
 
 #[tauri::command]
-async fn pick_folder(app: tauri::AppHandle) -> Result<Option<String>, String> {
+async fn pick_folder(app: tauri::AppHandle) -> Result<Option<String>, AppError> {
     use tauri_plugin_dialog::DialogExt;
     let (tx, rx) = tokio::sync::oneshot::channel();
     app.dialog().file().pick_folder(move |folder| {
         let _ = tx.send(folder);
     });
-    let folder = rx.await.map_err(|e| e.to_string())?;
+    let folder = rx.await.map_err(|e| AppError::Internal(e.to_string()))?;
     Ok(folder.map(|f| f.to_string()))
 }
 
 #[tauri::command]
-fn open_path(path: String) -> Result<(), String> {
+fn open_path(path: String) -> Result<(), AppError> {
     #[cfg(target_os = "windows")]
     {
         std::process::Command::new("explorer")
             .arg(&path)
             .spawn()
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::process("explorer", e.to_string()))?;
     }
     #[cfg(target_os = "macos")]
     {
         std::process::Command::new("open")
             .arg(&path)
             .spawn()
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::process("open", e.to_string()))?;
     }
     #[cfg(target_os = "linux")]
     {
         std::process::Command::new("xdg-open")
             .arg(&path)
             .spawn()
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::process("xdg-open", e.to_string()))?;
     }
     Ok(())
 }
@@ -413,22 +439,12 @@ async fn compress_video(
     format_ext: String,
     quality_crf: String,
     resolution: String,
-) -> Result<String, String> {
-    let bin_dir = binaries::get_bin_dir();
-    let ffmpeg_path = if cfg!(target_os = "windows") {
-        bin_dir.join("ffmpeg.exe")
-    } else {
-        bin_dir.join("ffmpeg")
-    };
-
-    if !ffmpeg_path.exists() {
-        return Err("ffmpeg not installed".to_string());
-    }
+) -> Result<String, AppError> {
+    let ffmpeg_path = tools::path(&app, "ffmpeg")?;
 
     let dest_dir = app
         .path()
-        .resolve("", tauri::path::BaseDirectory::Download)
-        .map_err(|e| e.to_string())?;
+        .resolve("", tauri::path::BaseDirectory::Download)?;
 
     let input_path_buf = std::path::PathBuf::from(&input_path);
     let original_name = input_path_buf
@@ -446,16 +462,13 @@ async fn compress_video(
         ),
     );
 
-    let mut cmd = std::process::Command::new(&ffmpeg_path);
-    #[cfg(target_os = "windows")]
-    {
-        use std::os::windows::process::CommandExt;
-        cmd.creation_flags(0x08000000);
-    }
+    let mut cmd = process::command(&ffmpeg_path);
 
     let vcodec = if format_ext == "webm" { "libvpx-vp9" } else { "libx264" };
     
     cmd.arg("-y") // Overwrite output files
+       .arg("-progress") // key=value progress blocks on stdout, newline terminated
+       .arg("pipe:1")
        .arg("-i")
        .arg(&input_path);
        
@@ -474,24 +487,56 @@ async fn compress_video(
        .stdout(std::process::Stdio::piped())
        .stderr(std::process::Stdio::piped());
 
-    let mut child = cmd.spawn().map_err(|e| e.to_string())?;
-    let stderr = child.stderr.take().unwrap();
-    let reader = std::io::BufReader::new(stderr);
-
-    use std::io::BufRead;
-    for line in reader.lines() {
-        if let Ok(l) = line {
-            // ffmpeg outputs progress to stderr
-            let _ = app.emit("compress-log", l);
+    let mut child = cmd.spawn().map_err(|e| AppError::process("ffmpeg", e.to_string()))?;
+    let mut stdout = process::lines(child.stdout.take().unwrap());
+    let stderr = process::lines(child.stderr.take().unwrap());
+    let stderr_app = app.clone();
+    let stderr_reader = tauri::async_runtime::spawn(process::drain_tail(stderr, move |l| {
+        // ffmpeg logs to stderr
+        let _ = stderr_app.emit("compress-log", l.clone());
+        l
+    }));
+
+    // ffmpeg writes a progress block about twice a second, so silence means it hung
+    loop {
+        match tokio::time::timeout(download::STALL_TIMEOUT, stdout.next_line()).await {
+            Ok(Ok(Some(_))) => {}
+            Ok(Ok(None)) => break,
+            Ok(Err(e)) => {
+                if let Some(pid) = child.id() {
+                    process::kill_tree(pid);
+                }
+                let _ = child.wait().await;
+                return Err(AppError::process("ffmpeg", e.to_string()));
+            }
+            Err(_) => {
+                if let Some(pid) = child.id() {
+                    process::kill_tree(pid);
+                }
+                let _ = child.wait().await;
+                let tail = stderr_reader.await.unwrap_or_default();
+                return Err(AppError::process(
+                    "ffmpeg",
+                    format!(
+                        "stalled, no progress for {} minutes\n{}",
+                        download::STALL_TIMEOUT.as_secs() / 60,
+                        tail.join("\n")
+                    ),
+                ));
+            }
         }
     }
 
-    let status = child.wait().map_err(|e| e.to_string())?;
+    let status = child.wait().await?;
+    let tail = stderr_reader.await.unwrap_or_default();
     if status.success() {
         let _ = app.emit("compress-log", "✅ Compressão concluída!".to_string());
         Ok(dest_path.to_string_lossy().to_string())
     } else {
-        Err("Processo do ffmpeg falhou".to_string())
+        Err(AppError::process(
+            "ffmpeg",
+            format!("exited with an error\n{}", tail.join("\n")),
+        ))
     }
 }
 
@@ -502,13 +547,13 @@ struct PickedVideo {
 }
 
 #[tauri::command]
-async fn pick_video_file(app: tauri::AppHandle) -> Result<Option<PickedVideo>, String> {
+async fn pick_video_file(app: tauri::AppHandle) -> Result<Option<PickedVideo>, AppError> {
     use tauri_plugin_dialog::DialogExt;
     let (tx, rx) = tokio::sync::oneshot::channel();
     app.dialog().file().add_filter("Video", &["mp4", "mkv", "avi", "mov", "webm", "flv"]).pick_file(move |file| {
         let _ = tx.send(file);
     });
-    let file = rx.await.map_err(|e| e.to_string())?;
+    let file = rx.await.map_err(|e| AppError::Internal(e.to_string()))?;
     
     if let Some(f) = file {
         let path = f.to_string();
@@ -525,22 +570,25 @@ async fn download_and_open_installer(
     app: tauri::AppHandle,
     url: String,
     ext: String,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     let _ = app.emit("download-log", format!("Iniciando download da atualização..."));
-    let response = reqwest::get(&url).await.map_err(|e| e.to_string())?;
+    let response = net::client(&app)
+        .get(&url)
+        .send()
+        .await?;
     
     let temp_dir = std::env::temp_dir();
     let fname = format!("mevideo_update_{}.{}", std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs(), ext.trim_start_matches('.'));
     let temp_file = temp_dir.join(&fname);
     
-    let mut file = tokio::fs::File::create(&temp_file).await.map_err(|e| e.to_string())?;
+    let mut file = tokio::fs::File::create(&temp_file).await?;
     
     use futures_util::StreamExt;
     use tokio::io::AsyncWriteExt;
     let mut stream = response.bytes_stream();
     while let Some(item) = stream.next().await {
-        let chunk = item.map_err(|e| e.to_string())?;
-        file.write_all(&chunk).await.map_err(|e| e.to_string())?;
+        let chunk = item?;
+        file.write_all(&chunk).await?;
     }
     
     let _ = app.emit("download-log", format!("Abrindo instalador..."));
@@ -553,7 +601,7 @@ async fn download_and_open_installer(
                 .arg("/i")
                 .arg(&path_str)
                 .spawn()
-                .map_err(|e| e.to_string())?;
+                .map_err(|e| AppError::process("installer", e.to_string()))?;
         } else {
             std::process::Command::new("cmd")
                 .arg("/c")
@@ -561,7 +609,7 @@ async fn download_and_open_installer(
                 .arg("")
                 .arg(&path_str)
                 .spawn()
-                .map_err(|e| e.to_string())?;
+                .map_err(|e| AppError::process("installer", e.to_string()))?;
         }
     }
     #[cfg(target_os = "macos")]
@@ -569,7 +617,7 @@ async fn download_and_open_installer(
         std::process::Command::new("open")
             .arg(&temp_file)
             .spawn()
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::process("installer", e.to_string()))?;
     }
     #[cfg(target_os = "linux")]
     {
@@ -582,7 +630,7 @@ async fn download_and_open_installer(
         std::process::Command::new("xdg-open")
             .arg(&temp_file)
             .spawn()
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::process("installer", e.to_string()))?;
     }
     
     Ok(())
@@ -599,7 +647,14 @@ pub fn run() {
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_http::init())
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
         .setup(|app| {
+            let settings_state = settings::SettingsState::load();
+            let proxy = settings_state.0.lock().unwrap().proxy.clone();
+            app.manage(settings_state);
+            app.manage(net::HttpClient::new(&proxy));
+            app.manage(queue::DownloadQueue::load());
+
             let quit_i = tauri::menu::MenuItem::with_id(app, "quit", "Sair", true, None::<&str>)?;
             let show_i =
                 tauri::menu::MenuItem::with_id(app, "show", "Abrir App", true, None::<&str>)?;
@@ -639,6 +694,13 @@ pub fn run() {
                 position_window_bottom_right(&window);
             }
 
+            // Pumping is a no-op until this has installed any auto-update and started the queue
+            updates::spawn_startup_check(app.handle().clone());
+            ratelimit::spawn_scheduler(app.handle().clone());
+            subscriptions::spawn_poller(app.handle().clone());
+            schedule::spawn_timer(app.handle().clone());
+            clipboard::spawn_watcher(app.handle().clone());
+
             Ok(())
         })
         .on_window_event(|window, event| {
@@ -650,16 +712,46 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             check_binary,
             get_binary_version,
+            checksums::verify_binary,
+            binaries::rollback_binary,
+            updates::check_binary_updates,
             download_binary,
             get_bin_path,
             open_bin_dir,
             download_video,
             get_video_info,
+            playlist::get_playlist_info,
+            playlist::enqueue_playlist_entries,
+            template::preview_output_template,
+            cookies::import_cookies,
+            cookies::list_cookie_profiles,
+            cookies::delete_cookie_profile,
             pick_folder,
             pick_video_file,
             compress_video,
             open_path,
-            download_and_open_installer
+            download_and_open_installer,
+            queue::enqueue_download,
+            queue::get_queue,
+            queue::cancel_download,
+            queue::pause_download,
+            queue::resume_download,
+            queue::retry_download,
+            queue::remove_job,
+            queue::clear_finished_jobs,
+            archive::list_archive,
+            archive::remove_archive_entries,
+            archive::clear_archive,
+            archive::import_archive,
+            archive::export_archive,
+            subscriptions::list_subscriptions,
+            subscriptions::add_subscription,
+            subscriptions::rename_subscription,
+            subscriptions::remove_subscription,
+            subscriptions::set_subscription_enabled,
+            subscriptions::check_subscriptions_now,
+            settings::get_settings,
+            settings::update_settings
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
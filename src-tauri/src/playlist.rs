@@ -0,0 +1,195 @@
+use crate::archive;
+use crate::cookies;
+use crate::download;
+use crate::download::{DownloadMode, DownloadOptions};
+use crate::error::AppError;
+use crate::failure;
+use crate::net;
+use crate::process;
+use crate::queue;
+use crate::settings;
+use crate::tools;
+
+const DEFAULT_PAGE_SIZE: u64 = 100;
+
+#[derive(serde::Serialize)]
+pub struct PlaylistEntry {
+    index: u64,
+    id: String,
+    url: String,
+    title: String,
+    duration: Option<f64>,
+    thumbnail: Option<String>,
+    archive_id: Option<String>,
+    // Already downloaded; enqueue_playlist_entries skips these
+    archived: bool,
+}
+
+// What the UI sends back for each entry it wants downloaded
+#[derive(serde::Deserialize)]
+pub struct EntrySelection {
+    url: String,
+    archive_id: Option<String>,
+}
+
+#[derive(serde::Serialize)]
+pub struct PlaylistInfo {
+    id: String,
+    title: String,
+    uploader: Option<String>,
+    // Not every extractor knows the total without walking the whole list
+    entry_count: Option<u64>,
+    start: u64,
+    has_more: bool,
+    entries: Vec<PlaylistEntry>,
+}
+
+fn entry_thumbnail(entry: &serde_json::Value) -> Option<String> {
+    if let Some(thumb) = entry["thumbnail"].as_str() {
+        return Some(thumb.to_string());
+    }
+    // Flat extraction usually only provides the thumbnail list, smallest first
+    entry["thumbnails"]
+        .as_array()?
+        .iter()
+        .rev()
+        .find_map(|t| t["url"].as_str())
+        .map(|s| s.to_string())
+}
+
+#[tauri::command]
+pub async fn get_playlist_info(
+    app: tauri::AppHandle,
+    url: String,
+    start: Option<u64>,
+    page_size: Option<u64>,
+    cookie_profile: Option<String>,
+) -> Result<PlaylistInfo, AppError> {
+    let yt_dlp_path = tools::path(&app, "yt-dlp")?;
+
+    let start = start.unwrap_or(1).max(1);
+    let page_size = page_size.unwrap_or(DEFAULT_PAGE_SIZE).max(1);
+    let end = start + page_size - 1;
+
+    let mut cmd = process::command(&yt_dlp_path);
+    cmd.args(net::yt_dlp_proxy_args(
+        &settings::current(&app).proxy,
+        &url,
+    )?);
+
+    if let Some(cookie_file) = cookies::cookie_file_for(&url, cookie_profile.as_deref())? {
+        cmd.arg("--cookies").arg(cookie_file);
+    }
+
+    cmd.arg("-J")
+        .arg("--flat-playlist")
+        .arg("--playlist-items")
+        .arg(format!("{}:{}", start, end))
+        .arg(&url);
+    let output = process::output(&mut cmd, "yt-dlp", process::QUERY_TIMEOUT).await?;
+
+    if !output.status.success() {
+        let stderr: Vec<String> = String::from_utf8_lossy(&output.stderr)
+            .lines()
+            .map(|l| l.to_string())
+            .collect();
+        return Err(failure::classify(&stderr, output.status.code()));
+    }
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+
+    let archived = archive::keys();
+    let mut entries = Vec::new();
+    if let Some(entries_array) = json["entries"].as_array() {
+        for (i, e) in entries_array.iter().enumerate() {
+            let id = e["id"].as_str().unwrap_or("").to_string();
+            let entry_url = e["url"]
+                .as_str()
+                .or_else(|| e["webpage_url"].as_str())
+                .unwrap_or(&id)
+                .to_string();
+            let archive_id = archive::key_for(e);
+            entries.push(PlaylistEntry {
+                index: e["playlist_index"].as_u64().unwrap_or(start + i as u64),
+                id,
+                url: entry_url,
+                title: e["title"].as_str().unwrap_or("Unknown").to_string(),
+                duration: e["duration"].as_f64(),
+                thumbnail: entry_thumbnail(e),
+                archived: archive_id.as_ref().is_some_and(|k| archived.contains(k)),
+                archive_id,
+            });
+        }
+    }
+
+    let entry_count = json["playlist_count"].as_u64();
+    let has_more = match entry_count {
+        Some(count) => end < count,
+        None => entries.len() as u64 == page_size,
+    };
+
+    Ok(PlaylistInfo {
+        id: json["id"].as_str().unwrap_or("").to_string(),
+        title: json["title"].as_str().unwrap_or("Unknown").to_string(),
+        uploader: json["uploader"]
+            .as_str()
+            .or_else(|| json["channel"].as_str())
+            .map(|s| s.to_string()),
+        entry_count,
+        start,
+        has_more,
+        entries,
+    })
+}
+
+// Per-video format ids differ across a playlist, so the shared choice is expressed
+// as a height/container pair that format selection can resolve for every entry.
+pub fn shared_format_id(options: &DownloadOptions, format_height: u64) -> String {
+    if options.mode == DownloadMode::Audio {
+        String::new()
+    } else if format_height > 0 {
+        format!("bestvideo[height<={}]", format_height)
+    } else {
+        "bestvideo".to_string()
+    }
+}
+
+#[tauri::command]
+pub fn enqueue_playlist_entries(
+    app: tauri::AppHandle,
+    entries: Vec<EntrySelection>,
+    format_ext: String,
+    format_height: u64,
+    custom_path: Option<String>,
+    options: Option<DownloadOptions>,
+) -> Result<Vec<queue::Job>, AppError> {
+    let dest = download::resolve_dest(&app, custom_path)?;
+    let options = options.unwrap_or_default();
+    let format_id = shared_format_id(&options, format_height);
+
+    let archived = if settings::current(&app).use_download_archive && !options.ignore_archive {
+        archive::keys()
+    } else {
+        Default::default()
+    };
+
+    let jobs = entries
+        .into_iter()
+        .filter(|e| !e.archive_id.as_ref().is_some_and(|k| archived.contains(k)))
+        .map(|e| {
+            let mut options = options.clone();
+            options.archive_id = e.archive_id;
+            queue::enqueue(
+                &app,
+                e.url,
+                format_id.clone(),
+                format_ext.clone(),
+                format_height,
+                dest.to_string_lossy().to_string(),
+                options,
+            )
+        })
+        .collect::<Result<Vec<_>, AppError>>()?;
+    queue::pump(&app);
+    Ok(jobs)
+}
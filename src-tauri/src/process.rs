@@ -0,0 +1,159 @@
+use std::collections::VecDeque;
+use std::ffi::OsStr;
+use std::process::{Output, Stdio};
+use std::time::Duration;
+
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+
+use crate::error::AppError;
+
+// Metadata queries such as `yt-dlp -J` on a long playlist can legitimately take a while
+pub const QUERY_TIMEOUT: Duration = Duration::from_secs(180);
+pub const VERSION_TIMEOUT: Duration = Duration::from_secs(30);
+// Only the end of stderr is kept for error reports; tools print the cause last
+pub const STDERR_TAIL_LINES: usize = 50;
+
+// Every tool runs through here: no console window on Windows, its own process group
+// so the whole tree can be signalled at once, and killed if the handle is dropped
+pub fn command(program: impl AsRef<OsStr>) -> tokio::process::Command {
+    let mut cmd = tokio::process::Command::new(program);
+    #[cfg(target_os = "windows")]
+    cmd.creation_flags(0x08000000);
+    #[cfg(unix)]
+    cmd.process_group(0);
+    cmd.stdin(Stdio::null()).kill_on_drop(true);
+    cmd
+}
+
+// Runs to completion and collects the output. When `timeout` passes the whole process
+// tree is killed, since kill_on_drop only reaches the direct child.
+pub async fn output(
+    cmd: &mut tokio::process::Command,
+    program: &str,
+    timeout: Duration,
+) -> Result<Output, AppError> {
+    let child = cmd
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| AppError::process(program, e.to_string()))?;
+    let pid = child.id();
+    match tokio::time::timeout(timeout, child.wait_with_output()).await {
+        Ok(result) => result.map_err(|e| AppError::process(program, e.to_string())),
+        Err(_) => {
+            if let Some(pid) = pid {
+                kill_tree(pid);
+            }
+            Err(AppError::process(
+                program,
+                format!("timed out after {}s", timeout.as_secs()),
+            ))
+        }
+    }
+}
+
+// Splits output on '\n' without requiring UTF-8. Windows tools often print in the
+// console code page, and a decoding error must not end the stream early: whatever
+// stops being read fills its pipe and blocks the child.
+pub struct Lines<R> {
+    reader: BufReader<R>,
+    buf: Vec<u8>,
+}
+
+impl<R: AsyncRead + Unpin> Lines<R> {
+    // Ok(None) only at the end of the output; invalid bytes are replaced
+    pub async fn next_line(&mut self) -> std::io::Result<Option<String>> {
+        self.buf.clear();
+        if self.reader.read_until(b'\n', &mut self.buf).await? == 0 {
+            return Ok(None);
+        }
+        let line = String::from_utf8_lossy(&self.buf);
+        Ok(Some(line.trim_end_matches(['\r', '\n']).to_string()))
+    }
+}
+
+pub fn lines<R: AsyncRead + Unpin>(reader: R) -> Lines<R> {
+    Lines {
+        reader: BufReader::new(reader),
+        buf: Vec::new(),
+    }
+}
+
+// Reads stderr to the end, passing each line through `on_line` and keeping the last
+// STDERR_TAIL_LINES of what it returns for error reports
+pub async fn drain_tail<R: AsyncRead + Unpin>(
+    mut lines: Lines<R>,
+    mut on_line: impl FnMut(String) -> String,
+) -> Vec<String> {
+    let mut tail = VecDeque::with_capacity(STDERR_TAIL_LINES);
+    loop {
+        let l = match lines.next_line().await {
+            Ok(Some(l)) => l,
+            Ok(None) | Err(_) => break,
+        };
+        if tail.len() == STDERR_TAIL_LINES {
+            tail.pop_front();
+        }
+        tail.push_back(on_line(l));
+    }
+    Vec::from(tail)
+}
+
+// yt-dlp spawns ffmpeg for merging, so killing only the direct child leaves orphans behind
+pub fn kill_tree(pid: u32) {
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        let _ = std::process::Command::new("taskkill")
+            .arg("/PID")
+            .arg(pid.to_string())
+            .arg("/T")
+            .arg("/F")
+            .creation_flags(0x08000000)
+            .status();
+    }
+    #[cfg(unix)]
+    {
+        let _ = std::process::Command::new("kill")
+            .arg("-KILL")
+            .arg(format!("-{}", pid))
+            .status();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn lines_survive_invalid_utf8() {
+        // "ação" in cp1252, as piped output looks on Windows
+        let output: &[u8] = b"first\r\nbaixando a\xe7\xe3o\nlast";
+        let mut lines = lines(output);
+        assert_eq!(lines.next_line().await.unwrap().as_deref(), Some("first"));
+        assert_eq!(
+            lines.next_line().await.unwrap().as_deref(),
+            Some("baixando a\u{fffd}\u{fffd}o")
+        );
+        assert_eq!(lines.next_line().await.unwrap().as_deref(), Some("last"));
+        assert_eq!(lines.next_line().await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn drain_tail_keeps_the_last_lines() {
+        let output: Vec<u8> = (0..STDERR_TAIL_LINES + 10)
+            .flat_map(|i| format!("line {}\n", i).into_bytes())
+            .chain(b"\xff\n".iter().copied())
+            .collect();
+        let mut seen = 0;
+        let tail = drain_tail(lines(output.as_slice()), |l| {
+            seen += 1;
+            l.to_uppercase()
+        })
+        .await;
+        assert_eq!(seen, STDERR_TAIL_LINES + 11);
+        assert_eq!(tail.len(), STDERR_TAIL_LINES);
+        assert_eq!(tail[0], "LINE 11");
+        assert_eq!(tail.last().map(String::as_str), Some("\u{fffd}"));
+    }
+}
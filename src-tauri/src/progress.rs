@@ -0,0 +1,169 @@
+// Machine-readable progress lines. Every field is a single whitespace-free token
+// and yt-dlp renders missing values as "NA".
+pub const DOWNLOAD_TEMPLATE: &str = "download:[mevideo-progress] %(progress.status)s %(progress.downloaded_bytes)s %(progress.total_bytes)s %(progress.total_bytes_estimate)s %(progress.speed)s %(progress.eta)s %(progress.fragment_index)s %(progress.fragment_count)s %(info.vcodec)s %(info.acodec)s";
+pub const POSTPROCESS_TEMPLATE: &str =
+    "postprocess:[mevideo-postprocess] %(progress.status)s %(progress.postprocessor)s";
+
+const DOWNLOAD_PREFIX: &str = "[mevideo-progress] ";
+const POSTPROCESS_PREFIX: &str = "[mevideo-postprocess] ";
+
+#[derive(Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    Video,
+    Audio,
+    Merging,
+    PostProcessing,
+}
+
+#[derive(Clone, serde::Serialize)]
+pub struct DownloadProgress {
+    pub job_id: String,
+    pub phase: Phase,
+    pub status: String,
+    pub downloaded_bytes: Option<u64>,
+    pub total_bytes: Option<u64>,
+    pub speed: Option<f64>,
+    pub eta: Option<u64>,
+    pub fragment_index: Option<u64>,
+    pub fragment_count: Option<u64>,
+    pub percent: Option<f64>,
+}
+
+fn field<T: std::str::FromStr>(token: Option<&str>) -> Option<T> {
+    token.filter(|t| *t != "NA" && *t != "None")?.parse().ok()
+}
+
+// yt-dlp prints integers for byte counts but floats for estimates
+fn field_u64(token: Option<&str>) -> Option<u64> {
+    field::<f64>(token).map(|v| v as u64)
+}
+
+pub fn is_progress_line(line: &str) -> bool {
+    line.starts_with(DOWNLOAD_PREFIX) || line.starts_with(POSTPROCESS_PREFIX)
+}
+
+pub fn parse_line(job_id: &str, line: &str) -> Option<DownloadProgress> {
+    if let Some(rest) = line.strip_prefix(DOWNLOAD_PREFIX) {
+        let mut tokens = rest.split_whitespace();
+        let status = tokens.next()?.to_string();
+        let downloaded_bytes = field_u64(tokens.next());
+        let total_bytes = field_u64(tokens.next());
+        let total_estimate = field_u64(tokens.next());
+        let speed = field::<f64>(tokens.next());
+        let eta = field_u64(tokens.next());
+        let fragment_index = field_u64(tokens.next());
+        let fragment_count = field_u64(tokens.next());
+        let vcodec = tokens.next().unwrap_or("NA");
+        let acodec = tokens.next().unwrap_or("NA");
+
+        // Merged downloads fetch each stream separately; the codecs tell them apart
+        let phase = if vcodec == "none" && acodec != "none" {
+            Phase::Audio
+        } else {
+            Phase::Video
+        };
+        let total_bytes = total_bytes.or(total_estimate);
+        let percent = match (downloaded_bytes, total_bytes) {
+            (Some(done), Some(total)) if total > 0 => {
+                Some((done as f64 / total as f64 * 100.0).min(100.0))
+            }
+            _ => None,
+        };
+
+        return Some(DownloadProgress {
+            job_id: job_id.to_string(),
+            phase,
+            status,
+            downloaded_bytes,
+            total_bytes,
+            speed,
+            eta,
+            fragment_index,
+            fragment_count,
+            percent,
+        });
+    }
+
+    if let Some(rest) = line.strip_prefix(POSTPROCESS_PREFIX) {
+        let mut tokens = rest.split_whitespace();
+        let status = tokens.next()?.to_string();
+        let postprocessor = tokens.next().unwrap_or("");
+        let phase = if postprocessor == "Merger" {
+            Phase::Merging
+        } else {
+            Phase::PostProcessing
+        };
+
+        return Some(DownloadProgress {
+            job_id: job_id.to_string(),
+            phase,
+            status,
+            downloaded_bytes: None,
+            total_bytes: None,
+            speed: None,
+            eta: None,
+            fragment_index: None,
+            fragment_count: None,
+            percent: None,
+        });
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_a_video_download_line() {
+        let line =
+            "[mevideo-progress] downloading 5242880 10485760 NA 1048576.5 5 NA NA avc1.64001F none";
+        let p = parse_line("job", line).unwrap();
+        assert!(p.phase == Phase::Video);
+        assert_eq!(p.status, "downloading");
+        assert_eq!(p.downloaded_bytes, Some(5_242_880));
+        assert_eq!(p.total_bytes, Some(10_485_760));
+        assert_eq!(p.speed, Some(1_048_576.5));
+        assert_eq!(p.eta, Some(5));
+        assert_eq!(p.fragment_index, None);
+        assert_eq!(p.percent, Some(50.0));
+    }
+
+    #[test]
+    fn audio_stream_uses_the_estimate_and_caps_percent() {
+        let line = "[mevideo-progress] downloading 2000 NA 1500.7 NA NA 3 10 none opus";
+        let p = parse_line("job", line).unwrap();
+        assert!(p.phase == Phase::Audio);
+        assert_eq!(p.total_bytes, Some(1500));
+        assert_eq!(p.fragment_index, Some(3));
+        assert_eq!(p.fragment_count, Some(10));
+        assert_eq!(p.percent, Some(100.0));
+    }
+
+    #[test]
+    fn missing_totals_leave_percent_empty() {
+        let p = parse_line("job", "[mevideo-progress] downloading 100 NA NA None NA").unwrap();
+        assert!(p.phase == Phase::Video);
+        assert_eq!(p.total_bytes, None);
+        assert_eq!(p.speed, None);
+        assert_eq!(p.percent, None);
+    }
+
+    #[test]
+    fn postprocess_lines_map_merger_to_merging() {
+        let merging = parse_line("job", "[mevideo-postprocess] started Merger").unwrap();
+        assert!(merging.phase == Phase::Merging);
+        assert_eq!(merging.status, "started");
+        let other = parse_line("job", "[mevideo-postprocess] finished FFmpegExtractAudio").unwrap();
+        assert!(other.phase == Phase::PostProcessing);
+    }
+
+    #[test]
+    fn other_lines_are_ignored() {
+        assert!(!is_progress_line("[download] Destination: video.mp4"));
+        assert!(parse_line("job", "[download] 50.0% of 10.00MiB").is_none());
+        assert!(parse_line("job", "[mevideo-progress] ").is_none());
+    }
+}
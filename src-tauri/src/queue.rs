@@ -0,0 +1,568 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use tauri::Emitter;
+use tauri::Manager;
+use tokio::sync::oneshot;
+
+use crate::download;
+use crate::download::{DownloadOptions, DownloadResult};
+use crate::error::AppError;
+use crate::failure;
+use crate::failure::ErrorKind;
+use crate::process;
+use crate::schedule;
+use crate::settings;
+use crate::storage;
+use crate::template;
+
+const QUEUE_FILE: &str = "queue.json";
+
+#[derive(Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Pending,
+    Running,
+    Paused,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct Job {
+    pub id: String,
+    pub url: String,
+    pub format_id: String,
+    pub format_ext: String,
+    pub format_height: u64,
+    pub dest: String,
+    #[serde(default)]
+    pub options: DownloadOptions,
+    pub status: JobStatus,
+    #[serde(default)]
+    pub result: Option<DownloadResult>,
+    #[serde(default)]
+    pub error: Option<String>,
+    #[serde(default)]
+    pub error_kind: Option<ErrorKind>,
+    // Automatic retries used so far
+    #[serde(default)]
+    pub attempts: u32,
+    // Destinations reported by yt-dlp, used to find partial files on cancel
+    #[serde(default)]
+    pub files: Vec<String>,
+    pub created_at: u64,
+    // Unix seconds before which a pending job is not started
+    #[serde(default)]
+    pub not_before: Option<u64>,
+}
+
+type Waiter = oneshot::Sender<Result<DownloadResult, AppError>>;
+
+#[derive(Default)]
+struct QueueInner {
+    jobs: Vec<Job>,
+    waiters: HashMap<String, Vec<Waiter>>,
+    pids: HashMap<String, u32>,
+    // Jobs with a live worker thread, including ones whose process is being stopped
+    workers: HashSet<String>,
+    // Nothing starts until the startup update check has finished
+    started: bool,
+}
+
+pub struct DownloadQueue {
+    inner: Mutex<QueueInner>,
+}
+
+pub(crate) fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+fn job_not_found(id: &str) -> AppError {
+    AppError::NotFound {
+        what: "job",
+        id: id.to_string(),
+    }
+}
+
+fn next_job_id() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    format!("{:x}-{}", nanos, COUNTER.fetch_add(1, Ordering::Relaxed))
+}
+
+impl DownloadQueue {
+    pub fn load() -> Self {
+        let mut jobs: Vec<Job> = storage::load_json(QUEUE_FILE).unwrap_or_default();
+        // Jobs that were running when the app closed start over
+        for job in jobs.iter_mut() {
+            if job.status == JobStatus::Running {
+                job.status = JobStatus::Pending;
+            }
+        }
+        Self {
+            inner: Mutex::new(QueueInner {
+                jobs,
+                waiters: HashMap::new(),
+                pids: HashMap::new(),
+                workers: HashSet::new(),
+                started: false,
+            }),
+        }
+    }
+
+    fn persist(&self, inner: &QueueInner) {
+        let _ = storage::save_json(QUEUE_FILE, &inner.jobs);
+    }
+
+    pub fn jobs(&self) -> Vec<Job> {
+        self.inner.lock().unwrap().jobs.clone()
+    }
+}
+
+fn emit_queue(app: &tauri::AppHandle) {
+    let jobs = app.state::<DownloadQueue>().jobs();
+    let _ = app.emit("queue-updated", jobs);
+}
+
+pub fn enqueue(
+    app: &tauri::AppHandle,
+    url: String,
+    format_id: String,
+    format_ext: String,
+    format_height: u64,
+    dest: String,
+    mut options: DownloadOptions,
+) -> Result<Job, AppError> {
+    let settings = settings::current(app);
+    let output_template = options
+        .output_template
+        .get_or_insert(settings.output_template);
+    template::validate(output_template)?;
+    download::validate_sections(&options.sections)?;
+    options
+        .restrict_filenames
+        .get_or_insert(settings.restrict_filenames);
+    options
+        .windows_filenames
+        .get_or_insert(settings.windows_filenames);
+    let not_before = match &options.schedule {
+        Some(s) => schedule::first_run(s)?,
+        None => None,
+    };
+    if settings.autostart_for_schedules
+        && options
+            .schedule
+            .as_ref()
+            .is_some_and(|s| not_before.is_some() || s.recurrence.is_some())
+    {
+        schedule::ensure_autostart(app);
+    }
+
+    let job = Job {
+        id: next_job_id(),
+        url,
+        format_id,
+        format_ext,
+        format_height,
+        dest,
+        options,
+        status: JobStatus::Pending,
+        result: None,
+        error: None,
+        error_kind: None,
+        attempts: 0,
+        files: Vec::new(),
+        created_at: now_secs(),
+        not_before,
+    };
+
+    let queue = app.state::<DownloadQueue>();
+    {
+        let mut inner = queue.inner.lock().unwrap();
+        inner.jobs.push(job.clone());
+        queue.persist(&inner);
+    }
+    emit_queue(app);
+    Ok(job)
+}
+
+// Resolves once the job leaves the queue, successfully or not
+pub fn wait_for(
+    app: &tauri::AppHandle,
+    id: &str,
+) -> oneshot::Receiver<Result<DownloadResult, AppError>> {
+    let (tx, rx) = oneshot::channel();
+    let queue = app.state::<DownloadQueue>();
+    let mut inner = queue.inner.lock().unwrap();
+    inner.waiters.entry(id.to_string()).or_default().push(tx);
+    rx
+}
+
+// Lets pump start jobs from now on and starts the pending ones
+pub fn start(app: &tauri::AppHandle) {
+    app.state::<DownloadQueue>().inner.lock().unwrap().started = true;
+    pump(app);
+}
+
+// Starts pending jobs until the concurrency limit is reached
+pub fn pump(app: &tauri::AppHandle) {
+    let max = settings::current(app).max_concurrent_downloads.max(1);
+    let queue = app.state::<DownloadQueue>();
+    let mut started = Vec::new();
+    {
+        let mut guard = queue.inner.lock().unwrap();
+        let inner = &mut *guard;
+        if !inner.started {
+            return;
+        }
+        let mut running = inner.workers.len();
+        for job in inner.jobs.iter_mut() {
+            if running >= max {
+                break;
+            }
+            // A restarted job is pending again while its old process is still exiting
+            if job.status == JobStatus::Pending
+                && !inner.workers.contains(&job.id)
+                && schedule::is_due(job.not_before)
+            {
+                job.status = JobStatus::Running;
+                inner.workers.insert(job.id.clone());
+                running += 1;
+                started.push(job.clone());
+            }
+        }
+        if started.is_empty() {
+            return;
+        }
+        queue.persist(inner);
+    }
+    emit_queue(app);
+
+    for job in started {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            let result = download::run(&app, &job).await;
+            finish(&app, &job.id, result);
+        });
+    }
+}
+
+// Returns false when the job was paused or cancelled before the process started,
+// in which case the caller must stop the process itself.
+pub fn register_pid(app: &tauri::AppHandle, id: &str, pid: u32) -> bool {
+    let queue = app.state::<DownloadQueue>();
+    let mut inner = queue.inner.lock().unwrap();
+    let running = inner
+        .jobs
+        .iter()
+        .any(|j| j.id == id && j.status == JobStatus::Running);
+    if running {
+        inner.pids.insert(id.to_string(), pid);
+    }
+    running
+}
+
+pub fn record_file(app: &tauri::AppHandle, id: &str, file: String) {
+    let queue = app.state::<DownloadQueue>();
+    let mut inner = queue.inner.lock().unwrap();
+    if let Some(job) = inner.jobs.iter_mut().find(|j| j.id == id) {
+        if !job.files.contains(&file) {
+            job.files.push(file);
+        }
+    }
+    queue.persist(&inner);
+}
+
+fn notify(inner: &mut QueueInner, id: &str, result: Result<DownloadResult, AppError>) {
+    if let Some(waiters) = inner.waiters.remove(id) {
+        for tx in waiters {
+            let _ = tx.send(result.clone());
+        }
+    }
+}
+
+// A recurring job leaves the finished run in the list and queues a copy for the next slot
+fn next_recurrence(job: &Job) -> Option<Job> {
+    let not_before = schedule::next_run(job.options.schedule.as_ref()?)?;
+    Some(Job {
+        id: next_job_id(),
+        status: JobStatus::Pending,
+        result: None,
+        error: None,
+        error_kind: None,
+        attempts: 0,
+        files: Vec::new(),
+        created_at: now_secs(),
+        not_before: Some(not_before),
+        ..job.clone()
+    })
+}
+
+fn finish(app: &tauri::AppHandle, id: &str, result: Result<DownloadResult, AppError>) {
+    let max_retries = settings::current(app).max_retries;
+    let queue = app.state::<DownloadQueue>();
+    let mut cancelled_files = None;
+    let mut retry_log = None;
+    {
+        let mut inner = queue.inner.lock().unwrap();
+        inner.pids.remove(id);
+        inner.workers.remove(id);
+        let mut stopped = None;
+        let mut repeat = None;
+        if let Some(job) = inner.jobs.iter_mut().find(|j| j.id == id) {
+            match &result {
+                Ok(output) => {
+                    job.status = JobStatus::Completed;
+                    job.result = Some(output.clone());
+                    job.error = None;
+                    job.error_kind = None;
+                }
+                // A killed process exits with an error; keep the state the user asked for
+                Err(_) if job.status == JobStatus::Paused => stopped = Some(JobStatus::Paused),
+                Err(_) if job.status == JobStatus::Pending => stopped = Some(JobStatus::Pending),
+                Err(_) if job.status == JobStatus::Cancelled => {
+                    stopped = Some(JobStatus::Cancelled);
+                    cancelled_files = Some(job.files.clone());
+                }
+                Err(e)
+                    if e.download_kind().is_some_and(ErrorKind::is_transient)
+                        && job.attempts < max_retries =>
+                {
+                    let kind = e.download_kind().unwrap();
+                    job.attempts += 1;
+                    let delay = failure::backoff_secs(kind, job.attempts);
+                    job.status = JobStatus::Pending;
+                    job.not_before = Some(now_secs() + delay);
+                    job.error = Some(e.to_string());
+                    job.error_kind = Some(kind);
+                    stopped = Some(JobStatus::Pending);
+                    retry_log = Some(format!(
+                        "{}. Nova tentativa ({}/{}) em {}s",
+                        e, job.attempts, max_retries, delay
+                    ));
+                }
+                Err(e) => {
+                    job.status = JobStatus::Failed;
+                    job.error = Some(e.to_string());
+                    job.error_kind = e.download_kind();
+                }
+            }
+            if matches!(job.status, JobStatus::Completed | JobStatus::Failed) {
+                repeat = next_recurrence(job);
+            }
+        }
+        if let Some(next) = repeat {
+            inner.jobs.push(next);
+        }
+        queue.persist(&inner);
+        match stopped {
+            // Paused and restarted jobs keep their waiters until they finish for real
+            Some(JobStatus::Paused | JobStatus::Pending) => {}
+            Some(_) => notify(&mut inner, id, Err(AppError::Cancelled)),
+            None => notify(&mut inner, id, result),
+        }
+    }
+    if let Some(message) = retry_log {
+        let _ = app.emit("download-log", message);
+    }
+    if let Some(files) = cancelled_files {
+        download::remove_partial_files(&files);
+    }
+    emit_queue(app);
+    pump(app);
+}
+
+// Stops every running process and puts its job back in the queue; yt-dlp picks
+// up the partial files with --continue when the job starts again
+pub fn restart_running(app: &tauri::AppHandle) {
+    let queue = app.state::<DownloadQueue>();
+    {
+        let mut guard = queue.inner.lock().unwrap();
+        let inner = &mut *guard;
+        for job in inner.jobs.iter_mut() {
+            if job.status != JobStatus::Running {
+                continue;
+            }
+            job.status = JobStatus::Pending;
+            if let Some(pid) = inner.pids.get(&job.id) {
+                process::kill_tree(*pid);
+            }
+        }
+        queue.persist(inner);
+    }
+    emit_queue(app);
+}
+
+// Moves a job to `target`, killing its process if it is currently running
+fn stop(app: &tauri::AppHandle, id: &str, target: JobStatus) -> Result<(), AppError> {
+    let queue = app.state::<DownloadQueue>();
+    let mut cancelled_files = None;
+    {
+        let mut inner = queue.inner.lock().unwrap();
+        let job = inner
+            .jobs
+            .iter_mut()
+            .find(|j| j.id == id)
+            .ok_or_else(|| job_not_found(id))?;
+        let was_running = job.status == JobStatus::Running;
+        match (job.status, target) {
+            (JobStatus::Pending | JobStatus::Running, JobStatus::Paused) => {}
+            (JobStatus::Pending | JobStatus::Running | JobStatus::Paused, JobStatus::Cancelled) => {
+            }
+            _ => {
+                return Err(AppError::InvalidState(
+                    "Job cannot be stopped in its current state".to_string(),
+                ))
+            }
+        }
+        job.status = target;
+        if !was_running && target == JobStatus::Cancelled {
+            cancelled_files = Some(job.files.clone());
+        }
+
+        if was_running {
+            // The worker sees the new status when the process exits and cleans up from there
+            if let Some(pid) = inner.pids.get(id) {
+                process::kill_tree(*pid);
+            }
+        } else if target == JobStatus::Cancelled {
+            notify(&mut inner, id, Err(AppError::Cancelled));
+        }
+        queue.persist(&inner);
+    }
+    if let Some(files) = cancelled_files {
+        download::remove_partial_files(&files);
+    }
+    emit_queue(app);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn enqueue_download(
+    app: tauri::AppHandle,
+    url: String,
+    format_id: String,
+    format_ext: String,
+    format_height: u64,
+    custom_path: Option<String>,
+    options: Option<DownloadOptions>,
+) -> Result<Job, AppError> {
+    let dest = download::resolve_dest(&app, custom_path)?;
+    let job = enqueue(
+        &app,
+        url,
+        format_id,
+        format_ext,
+        format_height,
+        dest.to_string_lossy().to_string(),
+        options.unwrap_or_default(),
+    )?;
+    pump(&app);
+    Ok(job)
+}
+
+#[tauri::command]
+pub fn cancel_download(app: tauri::AppHandle, id: String) -> Result<(), AppError> {
+    stop(&app, &id, JobStatus::Cancelled)
+}
+
+#[tauri::command]
+pub fn pause_download(app: tauri::AppHandle, id: String) -> Result<(), AppError> {
+    stop(&app, &id, JobStatus::Paused)
+}
+
+#[tauri::command]
+pub fn resume_download(app: tauri::AppHandle, id: String) -> Result<(), AppError> {
+    let queue = app.state::<DownloadQueue>();
+    {
+        let mut inner = queue.inner.lock().unwrap();
+        let job = inner
+            .jobs
+            .iter_mut()
+            .find(|j| j.id == id)
+            .ok_or_else(|| job_not_found(&id))?;
+        if job.status != JobStatus::Paused {
+            return Err(AppError::InvalidState("Job is not paused".to_string()));
+        }
+        job.status = JobStatus::Pending;
+        queue.persist(&inner);
+    }
+    emit_queue(&app);
+    pump(&app);
+    Ok(())
+}
+
+// Starts a failed job over with a fresh retry budget
+#[tauri::command]
+pub fn retry_download(app: tauri::AppHandle, id: String) -> Result<(), AppError> {
+    let queue = app.state::<DownloadQueue>();
+    {
+        let mut inner = queue.inner.lock().unwrap();
+        let job = inner
+            .jobs
+            .iter_mut()
+            .find(|j| j.id == id)
+            .ok_or_else(|| job_not_found(&id))?;
+        if job.status != JobStatus::Failed {
+            return Err(AppError::InvalidState("Job has not failed".to_string()));
+        }
+        job.status = JobStatus::Pending;
+        job.attempts = 0;
+        job.not_before = None;
+        queue.persist(&inner);
+    }
+    emit_queue(&app);
+    pump(&app);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_queue(queue: tauri::State<'_, DownloadQueue>) -> Vec<Job> {
+    queue.jobs()
+}
+
+#[tauri::command]
+pub fn remove_job(app: tauri::AppHandle, id: String) -> Result<(), AppError> {
+    let queue = app.state::<DownloadQueue>();
+    {
+        let mut inner = queue.inner.lock().unwrap();
+        let job = inner
+            .jobs
+            .iter()
+            .find(|j| j.id == id)
+            .ok_or_else(|| job_not_found(&id))?;
+        if job.status == JobStatus::Running {
+            return Err(AppError::InvalidState("Job is running".to_string()));
+        }
+        inner.jobs.retain(|j| j.id != id);
+        inner.waiters.remove(&id);
+        queue.persist(&inner);
+    }
+    emit_queue(&app);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn clear_finished_jobs(app: tauri::AppHandle) {
+    let queue = app.state::<DownloadQueue>();
+    {
+        let mut inner = queue.inner.lock().unwrap();
+        inner.jobs.retain(|j| {
+            matches!(
+                j.status,
+                JobStatus::Pending | JobStatus::Running | JobStatus::Paused
+            )
+        });
+        queue.persist(&inner);
+    }
+    emit_queue(&app);
+}
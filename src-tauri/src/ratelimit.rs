@@ -0,0 +1,87 @@
+use chrono::Timelike;
+use tauri::Emitter;
+
+use crate::error::AppError;
+use crate::queue;
+use crate::queue::Job;
+use crate::settings;
+use crate::settings::Settings;
+
+const CHECK_INTERVAL_SECS: u64 = 30;
+
+// Hours are local time; a window with start_hour > end_hour wraps past midnight
+#[derive(Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct RateWindow {
+    pub start_hour: u8,
+    pub end_hour: u8,
+    // Total bytes per second for all downloads; None means unlimited
+    pub limit: Option<u64>,
+}
+
+impl RateWindow {
+    fn contains(&self, hour: u8) -> bool {
+        if self.start_hour <= self.end_hour {
+            hour >= self.start_hour && hour < self.end_hour
+        } else {
+            hour >= self.start_hour || hour < self.end_hour
+        }
+    }
+}
+
+pub fn validate(settings: &Settings) -> Result<(), AppError> {
+    for w in &settings.rate_schedule {
+        if w.start_hour > 23 || w.end_hour > 24 || w.start_hour == w.end_hour {
+            return Err(AppError::invalid(
+                "rate_schedule",
+                "Invalid rate schedule window",
+            ));
+        }
+    }
+    Ok(())
+}
+
+// The first matching schedule window wins; outside all windows the global limit applies
+fn global_limit(settings: &Settings) -> Option<u64> {
+    let hour = chrono::Local::now().hour() as u8;
+    match settings.rate_schedule.iter().find(|w| w.contains(hour)) {
+        Some(window) => window.limit,
+        None => settings.rate_limit,
+    }
+}
+
+// yt-dlp cannot change --limit-rate while running, so every download slot gets a
+// fixed slice of the global budget; however many run, together they stay within it
+fn share(settings: &Settings) -> Option<u64> {
+    let slots = settings.max_concurrent_downloads.max(1) as u64;
+    global_limit(settings).map(|limit| (limit / slots).max(1))
+}
+
+pub fn limit_for(app: &tauri::AppHandle, job: &Job) -> Option<u64> {
+    let share = share(&settings::current(app));
+    match (share, job.options.rate_limit) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+// Restarts running downloads whenever the per-download share changes, either because
+// a schedule window opened or closed or because the limit or concurrency was edited
+pub fn spawn_scheduler(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut last = share(&settings::current(&app));
+        let mut interval =
+            tokio::time::interval(std::time::Duration::from_secs(CHECK_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            let current = share(&settings::current(&app));
+            if current != last {
+                last = current;
+                let _ = app.emit(
+                    "download-log",
+                    "Limite de banda alterado, reiniciando downloads...".to_string(),
+                );
+                queue::restart_running(&app);
+            }
+        }
+    });
+}
@@ -0,0 +1,328 @@
+use chrono::{Datelike, TimeZone, Timelike};
+
+use crate::error::AppError;
+use crate::queue;
+
+const TICK_SECS: u64 = 30;
+// A rule that matches nothing within a year (e.g. "0 0 31 2 *") is treated as invalid
+const MAX_LOOKAHEAD_MINUTES: i64 = 366 * 24 * 60;
+
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct Schedule {
+    // Unix seconds; the job stays pending until then
+    pub not_before: Option<u64>,
+    // Five-field cron rule in local time, e.g. "30 2 * * 1-5"; the job is queued
+    // again for the next matching minute after every run
+    pub recurrence: Option<String>,
+}
+
+struct Field {
+    allowed: Vec<bool>,
+    // "*" in day-of-month/day-of-week changes how the two are combined
+    any: bool,
+}
+
+impl Field {
+    fn matches(&self, value: u32) -> bool {
+        self.allowed.get(value as usize).copied().unwrap_or(false)
+    }
+}
+
+struct Cron {
+    minute: Field,
+    hour: Field,
+    day: Field,
+    month: Field,
+    weekday: Field,
+}
+
+// Supports *, numbers, lists (1,15), ranges (1-5) and steps (*/15, 0-30/10)
+fn parse_field(spec: &str, min: u32, max: u32) -> Result<Field, AppError> {
+    let invalid = || AppError::invalid("recurrence", format!("Invalid cron field '{}'", spec));
+    let mut allowed = vec![false; max as usize + 1];
+    for part in spec.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((r, s)) => (r, s.parse::<u32>().map_err(|_| invalid())?),
+            None => (part, 1),
+        };
+        if step == 0 {
+            return Err(invalid());
+        }
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((a, b)) = range.split_once('-') {
+            (
+                a.parse().map_err(|_| invalid())?,
+                b.parse().map_err(|_| invalid())?,
+            )
+        } else {
+            let v = range.parse().map_err(|_| invalid())?;
+            // "5/15" means from 5 to the end in steps of 15
+            (v, if part.contains('/') { max } else { v })
+        };
+        if start < min || end > max || start > end {
+            return Err(invalid());
+        }
+        for v in (start..=end).step_by(step as usize) {
+            allowed[v as usize] = true;
+        }
+    }
+    Ok(Field {
+        allowed,
+        any: spec.starts_with('*'),
+    })
+}
+
+fn parse(rule: &str) -> Result<Cron, AppError> {
+    let fields: Vec<&str> = rule.split_whitespace().collect();
+    if fields.len() != 5 {
+        return Err(AppError::invalid(
+            "recurrence",
+            "Cron rules need five fields: minute hour day month weekday",
+        ));
+    }
+    let mut weekday = parse_field(fields[4], 0, 7)?;
+    // Both 0 and 7 mean Sunday
+    if weekday.allowed[7] {
+        weekday.allowed[0] = true;
+    }
+    Ok(Cron {
+        minute: parse_field(fields[0], 0, 59)?,
+        hour: parse_field(fields[1], 0, 23)?,
+        day: parse_field(fields[2], 1, 31)?,
+        month: parse_field(fields[3], 1, 12)?,
+        weekday,
+    })
+}
+
+impl Cron {
+    fn matches(&self, t: &chrono::NaiveDateTime) -> bool {
+        let day = self.day.matches(t.day());
+        let weekday = self.weekday.matches(t.weekday().num_days_from_sunday());
+        // Like cron, a restricted day-of-month and day-of-week match if either does
+        let date = match (self.day.any, self.weekday.any) {
+            (false, false) => day || weekday,
+            _ => day && weekday,
+        };
+        date && self.month.matches(t.month())
+            && self.hour.matches(t.hour())
+            && self.minute.matches(t.minute())
+    }
+}
+
+// First matching minute strictly after `after` (unix seconds), in local time
+pub fn next_occurrence(rule: &str, after: u64) -> Result<u64, AppError> {
+    let cron = parse(rule)?;
+    let start = chrono::Local
+        .timestamp_opt(after as i64, 0)
+        .single()
+        .ok_or_else(|| AppError::invalid("not_before", "Invalid time"))?
+        .naive_local()
+        .with_second(0)
+        .unwrap();
+    for i in 1..=MAX_LOOKAHEAD_MINUTES {
+        let t = start + chrono::Duration::minutes(i);
+        if !cron.matches(&t) {
+            continue;
+        }
+        // Local times skipped by a DST change do not exist and are passed over; times
+        // repeated when clocks go back run at the first of the two instants
+        let local = match chrono::Local.from_local_datetime(&t) {
+            chrono::LocalResult::Single(l) => Some(l),
+            chrono::LocalResult::Ambiguous(a, b) => Some(a.min(b)),
+            chrono::LocalResult::None => None,
+        };
+        if let Some(local) = local {
+            let ts = local.timestamp() as u64;
+            if ts > after {
+                return Ok(ts);
+            }
+        }
+    }
+    Err(AppError::invalid(
+        "recurrence",
+        format!("Cron rule '{}' never matches", rule),
+    ))
+}
+
+// When a job becomes due: the later of the fixed time and the next rule match
+pub fn first_run(schedule: &Schedule) -> Result<Option<u64>, AppError> {
+    let now = queue::now_secs();
+    let floor = schedule.not_before.unwrap_or(0);
+    match &schedule.recurrence {
+        Some(rule) => Ok(Some(next_occurrence(rule, floor.max(now))?)),
+        None => Ok(schedule.not_before.filter(|&t| t > now)),
+    }
+}
+
+pub fn next_run(schedule: &Schedule) -> Option<u64> {
+    let rule = schedule.recurrence.as_deref()?;
+    next_occurrence(rule, queue::now_secs()).ok()
+}
+
+pub fn is_due(not_before: Option<u64>) -> bool {
+    not_before.map_or(true, |t| t <= queue::now_secs())
+}
+
+// Scheduled jobs only start if the app is running, so make sure it launches at login
+pub fn ensure_autostart(app: &tauri::AppHandle) {
+    use tauri_plugin_autostart::ManagerExt;
+    let autolaunch = app.autolaunch();
+    if !autolaunch.is_enabled().unwrap_or(false) {
+        let _ = autolaunch.enable();
+    }
+}
+
+// Wakes the queue so jobs whose time has come get started
+pub fn spawn_timer(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(TICK_SECS));
+        loop {
+            interval.tick().await;
+            queue::pump(&app);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // US Eastern as a POSIX rule so the tests do not depend on the system tz database.
+    // Every test that reads local time sets the same value, so they can run in parallel.
+    // Only unix reads TZ, hence the cfg on those tests.
+    #[cfg(unix)]
+    fn use_new_york() {
+        std::env::set_var("TZ", "EST5EDT,M3.2.0,M11.1.0");
+    }
+
+    #[cfg(unix)]
+    fn ts(rfc3339: &str) -> u64 {
+        chrono::DateTime::parse_from_rfc3339(rfc3339)
+            .unwrap()
+            .timestamp() as u64
+    }
+
+    #[test]
+    fn parses_lists_ranges_and_steps() {
+        let cron = parse("*/15 9-17 1,15 * 1-5").unwrap();
+        let minutes: Vec<u32> = (0..60).filter(|&m| cron.minute.matches(m)).collect();
+        assert_eq!(minutes, [0, 15, 30, 45]);
+        assert!(cron.hour.matches(9) && cron.hour.matches(17) && !cron.hour.matches(18));
+        assert!(cron.day.matches(15) && !cron.day.matches(2));
+        assert!(cron.month.any);
+
+        let cron = parse("5/20 0-10/5 * * *").unwrap();
+        let minutes: Vec<u32> = (0..60).filter(|&m| cron.minute.matches(m)).collect();
+        assert_eq!(minutes, [5, 25, 45]);
+        let hours: Vec<u32> = (0..24).filter(|&h| cron.hour.matches(h)).collect();
+        assert_eq!(hours, [0, 5, 10]);
+    }
+
+    #[test]
+    fn seven_is_also_sunday() {
+        let cron = parse("0 0 * * 7").unwrap();
+        assert!(cron.weekday.matches(0));
+    }
+
+    #[test]
+    fn rejects_malformed_rules() {
+        for rule in [
+            "* * * *",
+            "* * * * * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "* * * 13 *",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "a * * * *",
+            "1-x * * * *",
+        ] {
+            assert!(parse(rule).is_err(), "{}", rule);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn next_occurrence_is_strictly_after() {
+        use_new_york();
+        let after = ts("2024-06-03T08:30:00-04:00");
+        assert_eq!(
+            next_occurrence("30 8 * * *", after).unwrap(),
+            ts("2024-06-04T08:30:00-04:00")
+        );
+        assert_eq!(
+            next_occurrence("*/10 * * * *", after + 5).unwrap(),
+            ts("2024-06-03T08:40:00-04:00")
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn restricted_day_and_weekday_match_either() {
+        use_new_york();
+        // October 2024: the 13th is a Sunday, the Fridays are the 4th, 11th, 18th and 25th
+        let friday = ts("2024-10-11T12:00:00-04:00");
+        assert_eq!(
+            next_occurrence("0 12 13 * 5", friday).unwrap(),
+            ts("2024-10-13T12:00:00-04:00")
+        );
+        assert_eq!(
+            next_occurrence("0 12 13 * 5", ts("2024-10-13T12:00:00-04:00")).unwrap(),
+            ts("2024-10-18T12:00:00-04:00")
+        );
+        // With either field left as "*" both have to match
+        assert_eq!(
+            next_occurrence("0 12 * * 5", friday).unwrap(),
+            ts("2024-10-18T12:00:00-04:00")
+        );
+        assert_eq!(
+            next_occurrence("0 12 */2 * 5", friday).unwrap(),
+            ts("2024-10-25T12:00:00-04:00")
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn skips_times_lost_to_spring_forward() {
+        use_new_york();
+        // 2024-03-10 goes from 01:59 EST straight to 03:00 EDT
+        let after = ts("2024-03-10T00:00:00-05:00");
+        assert_eq!(
+            next_occurrence("30 2 * * *", after).unwrap(),
+            ts("2024-03-11T02:30:00-04:00")
+        );
+        assert_eq!(
+            next_occurrence("0 3 * * *", after).unwrap(),
+            ts("2024-03-10T03:00:00-04:00")
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn runs_once_when_fall_back_repeats_an_hour() {
+        use_new_york();
+        // 2024-11-03 goes through 01:00-01:59 twice, first in EDT then in EST
+        let first = next_occurrence("30 1 * * *", ts("2024-11-03T00:00:00-04:00")).unwrap();
+        assert_eq!(first, ts("2024-11-03T01:30:00-04:00"));
+        assert_eq!(
+            next_occurrence("30 1 * * *", first).unwrap(),
+            ts("2024-11-04T01:30:00-05:00")
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn impossible_dates_never_match() {
+        use_new_york();
+        assert!(next_occurrence("0 0 31 2 *", ts("2024-01-01T00:00:00-05:00")).is_err());
+        assert_eq!(
+            next_occurrence("0 0 29 2 *", ts("2024-03-01T00:00:00-05:00"))
+                .unwrap_err()
+                .code(),
+            "invalid_input"
+        );
+    }
+}
@@ -0,0 +1,102 @@
+use std::sync::Mutex;
+
+use tauri::Manager;
+
+use crate::clipboard;
+use crate::clipboard::ClipboardSettings;
+use crate::download::EmbedOptions;
+use crate::error::AppError;
+use crate::net;
+use crate::net::ProxySettings;
+use crate::queue;
+use crate::ratelimit;
+use crate::ratelimit::RateWindow;
+use crate::storage;
+use crate::template;
+use crate::tools;
+use crate::tools::ToolSettings;
+use crate::updates;
+use crate::updates::UpdateSettings;
+
+const SETTINGS_FILE: &str = "settings.json";
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub max_concurrent_downloads: usize,
+    // Automatic retries for network and throttling errors
+    pub max_retries: u32,
+    pub embed: EmbedOptions,
+    pub output_template: String,
+    pub restrict_filenames: bool,
+    pub windows_filenames: bool,
+    pub proxy: ProxySettings,
+    // Bytes per second shared by all downloads; None is unlimited
+    pub rate_limit: Option<u64>,
+    pub rate_schedule: Vec<RateWindow>,
+    // Skip videos already recorded in the download archive
+    pub use_download_archive: bool,
+    // How often subscriptions are polled; 0 disables polling
+    pub subscription_interval_minutes: u64,
+    // Turn on launch at login when a download is scheduled
+    pub autostart_for_schedules: bool,
+    pub clipboard: ClipboardSettings,
+    pub updates: UpdateSettings,
+    pub tools: ToolSettings,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            max_concurrent_downloads: 2,
+            max_retries: 3,
+            embed: EmbedOptions::default(),
+            output_template: template::DEFAULT_TEMPLATE.to_string(),
+            restrict_filenames: false,
+            windows_filenames: false,
+            proxy: ProxySettings::default(),
+            rate_limit: None,
+            rate_schedule: Vec::new(),
+            use_download_archive: true,
+            subscription_interval_minutes: 60,
+            autostart_for_schedules: true,
+            clipboard: ClipboardSettings::default(),
+            updates: UpdateSettings::default(),
+            tools: ToolSettings::default(),
+        }
+    }
+}
+
+pub struct SettingsState(pub Mutex<Settings>);
+
+impl SettingsState {
+    pub fn load() -> Self {
+        let settings = storage::load_json(SETTINGS_FILE).unwrap_or_default();
+        Self(Mutex::new(settings))
+    }
+}
+
+pub fn current(app: &tauri::AppHandle) -> Settings {
+    app.state::<SettingsState>().0.lock().unwrap().clone()
+}
+
+#[tauri::command]
+pub fn get_settings(state: tauri::State<'_, SettingsState>) -> Settings {
+    state.0.lock().unwrap().clone()
+}
+
+#[tauri::command]
+pub fn update_settings(app: tauri::AppHandle, settings: Settings) -> Result<(), AppError> {
+    template::validate(&settings.output_template)?;
+    net::validate(&settings.proxy)?;
+    ratelimit::validate(&settings)?;
+    clipboard::validate(&settings.clipboard)?;
+    updates::validate(&settings.updates)?;
+    tools::validate(&settings.tools)?;
+    storage::save_json(SETTINGS_FILE, &settings)?;
+    app.state::<net::HttpClient>().reload(&settings.proxy)?;
+    *app.state::<SettingsState>().0.lock().unwrap() = settings;
+    // A higher concurrency limit may free slots for pending jobs
+    queue::pump(&app);
+    Ok(())
+}
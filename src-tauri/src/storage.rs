@@ -0,0 +1,27 @@
+use std::fs;
+use std::path::PathBuf;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::binaries;
+use crate::error::AppError;
+
+pub fn data_file(name: &str) -> Result<PathBuf, AppError> {
+    Ok(binaries::get_data_dir()?.join(name))
+}
+
+pub fn load_json<T: DeserializeOwned>(name: &str) -> Option<T> {
+    let content = fs::read_to_string(data_file(name).ok()?).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+// Write to a sibling temp file first so a crash mid-write never leaves a truncated file
+pub fn save_json<T: Serialize>(name: &str, value: &T) -> Result<(), AppError> {
+    let path = data_file(name)?;
+    let tmp_path = path.with_extension("json.tmp");
+    let json = serde_json::to_string_pretty(value)?;
+    fs::write(&tmp_path, json)?;
+    fs::rename(&tmp_path, &path)?;
+    Ok(())
+}
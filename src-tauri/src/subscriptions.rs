@@ -0,0 +1,460 @@
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::Mutex;
+
+use tauri::Emitter;
+use tauri::Manager;
+
+use crate::archive;
+use crate::cookies;
+use crate::download;
+use crate::download::DownloadOptions;
+use crate::error::AppError;
+use crate::failure;
+use crate::net;
+use crate::net::ProxySettings;
+use crate::playlist;
+use crate::process;
+use crate::queue;
+use crate::settings;
+use crate::storage;
+use crate::tools;
+
+const SUBSCRIPTIONS_FILE: &str = "subscriptions.json";
+// Only the newest entries are looked at; channels list newest first
+const POLL_DEPTH: u64 = 30;
+// Enough history to not re-download items that drop out of the polled window and back
+const MAX_SEEN: usize = 500;
+const TICK_SECS: u64 = 60;
+
+// Commands and the poller both load, modify and save the file
+static SUBSCRIPTIONS_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct Subscription {
+    pub id: String,
+    pub url: String,
+    pub name: String,
+    pub format_ext: String,
+    pub format_height: u64,
+    pub dest: String,
+    #[serde(default)]
+    pub options: DownloadOptions,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    // Archive keys ("extractor id") of entries already handled, newest last
+    #[serde(default)]
+    pub seen: Vec<String>,
+    #[serde(default)]
+    pub last_checked: Option<u64>,
+    #[serde(default)]
+    pub last_error: Option<String>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+pub struct FeedEntry {
+    pub url: String,
+    pub key: String,
+}
+
+fn next_subscription_id() -> String {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    format!("{:x}", nanos)
+}
+
+fn load_subscriptions() -> Vec<Subscription> {
+    storage::load_json(SUBSCRIPTIONS_FILE).unwrap_or_default()
+}
+
+fn update_subscription(id: &str, f: impl FnOnce(&mut Subscription)) -> Result<(), AppError> {
+    let _guard = SUBSCRIPTIONS_LOCK.lock().unwrap();
+    let mut subs = load_subscriptions();
+    // Removed while it was being checked
+    let Some(sub) = subs.iter_mut().find(|s| s.id == id) else {
+        return Ok(());
+    };
+    f(sub);
+    storage::save_json(SUBSCRIPTIONS_FILE, &subs)
+}
+
+pub struct Feed {
+    pub title: Option<String>,
+    pub entries: Vec<FeedEntry>,
+}
+
+// Lists the newest entries of a channel or playlist. Takes the yt-dlp path and
+// cookie file so it can be pointed at any executable that prints the same JSON
+// without touching the app's data folder.
+pub async fn fetch_feed(
+    yt_dlp_path: &Path,
+    sub: &Subscription,
+    cookie_file: Option<&Path>,
+    proxy: &ProxySettings,
+) -> Result<Feed, AppError> {
+    let mut cmd = process::command(yt_dlp_path);
+    cmd.args(net::yt_dlp_proxy_args(proxy, &sub.url)?);
+    if let Some(cookie_file) = cookie_file {
+        cmd.arg("--cookies").arg(cookie_file);
+    }
+
+    cmd.arg("-J")
+        .arg("--flat-playlist")
+        .arg("--playlist-items")
+        .arg(format!("1:{}", POLL_DEPTH))
+        .arg(&sub.url);
+    let output = process::output(&mut cmd, "yt-dlp", process::QUERY_TIMEOUT).await?;
+
+    if !output.status.success() {
+        let stderr: Vec<String> = String::from_utf8_lossy(&output.stderr)
+            .lines()
+            .map(|l| l.to_string())
+            .collect();
+        return Err(failure::classify(&stderr, output.status.code()));
+    }
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+
+    let mut entries = Vec::new();
+    for e in json["entries"].as_array().into_iter().flatten() {
+        let Some(key) = archive::key_for(e) else {
+            continue;
+        };
+        let Some(url) = e["url"].as_str().or_else(|| e["webpage_url"].as_str()) else {
+            continue;
+        };
+        entries.push(FeedEntry {
+            url: url.to_string(),
+            key,
+        });
+    }
+    Ok(Feed {
+        title: json["title"].as_str().map(|s| s.to_string()),
+        entries,
+    })
+}
+
+// Entries not seen before, not in the archive and not already queued, oldest first
+// so the downloads finish in publishing order
+fn new_entries(
+    seen: &[String],
+    archived: &HashSet<String>,
+    queued: &[String],
+    entries: Vec<FeedEntry>,
+) -> Vec<FeedEntry> {
+    let mut fresh: Vec<FeedEntry> = entries
+        .into_iter()
+        .filter(|e| {
+            !seen.contains(&e.key) && !archived.contains(&e.key) && !queued.contains(&e.url)
+        })
+        .collect();
+    fresh.reverse();
+    fresh
+}
+
+// Keeps only the newest MAX_SEEN keys
+fn mark_seen(seen: &mut Vec<String>, keys: impl IntoIterator<Item = String>) {
+    for key in keys {
+        if !seen.contains(&key) {
+            seen.push(key);
+        }
+    }
+    if seen.len() > MAX_SEEN {
+        let excess = seen.len() - MAX_SEEN;
+        seen.drain(..excess);
+    }
+}
+
+// Polls one subscription, enqueues its new items and records them as seen, returning
+// how many were queued. With enqueue false the current items are only marked as seen.
+// The caller persists the updated subscription.
+pub async fn poll(
+    app: &tauri::AppHandle,
+    yt_dlp_path: &Path,
+    sub: &mut Subscription,
+    enqueue: bool,
+) -> Result<usize, AppError> {
+    sub.last_checked = Some(queue::now_secs());
+    let proxy = settings::current(app).proxy;
+    let fetched = async {
+        let cookie_file =
+            cookies::cookie_file_for(&sub.url, sub.options.cookie_profile.as_deref())?;
+        fetch_feed(yt_dlp_path, sub, cookie_file.as_deref(), &proxy).await
+    };
+    let feed = match fetched.await {
+        Ok(feed) => feed,
+        Err(e) => {
+            sub.last_error = Some(e.to_string());
+            return Err(e);
+        }
+    };
+    sub.last_error = None;
+    if let Some(title) = feed.title {
+        if sub.name == sub.url {
+            sub.name = title;
+        }
+    }
+
+    let queued_urls: Vec<String> = app
+        .state::<queue::DownloadQueue>()
+        .jobs()
+        .into_iter()
+        .map(|j| j.url)
+        .collect();
+    let fresh = new_entries(&sub.seen, &archive::keys(), &queued_urls, feed.entries);
+    let mut queued = 0;
+    if enqueue {
+        let format_id = playlist::shared_format_id(&sub.options, sub.format_height);
+        for entry in &fresh {
+            let mut options = sub.options.clone();
+            options.archive_id = Some(entry.key.clone());
+            queue::enqueue(
+                app,
+                entry.url.clone(),
+                format_id.clone(),
+                sub.format_ext.clone(),
+                sub.format_height,
+                sub.dest.clone(),
+                options,
+            )?;
+            mark_seen(&mut sub.seen, [entry.key.clone()]);
+            queued += 1;
+        }
+        if queued > 0 {
+            queue::pump(app);
+        }
+    } else {
+        mark_seen(&mut sub.seen, fresh.into_iter().map(|e| e.key));
+    }
+    Ok(queued)
+}
+
+fn is_due(sub: &Subscription, interval_secs: u64) -> bool {
+    sub.last_checked
+        .map_or(true, |last| queue::now_secs() >= last + interval_secs)
+}
+
+async fn check_due(app: &tauri::AppHandle, force: bool) -> usize {
+    let interval_secs = settings::current(app).subscription_interval_minutes * 60;
+    let Ok(yt_dlp_path) = tools::path(app, "yt-dlp") else {
+        return 0;
+    };
+    let mut queued = 0;
+    for mut sub in load_subscriptions() {
+        if !sub.enabled || (!force && !is_due(&sub, interval_secs)) {
+            continue;
+        }
+        let had_placeholder = sub.name == sub.url;
+        let result = poll(app, &yt_dlp_path, &mut sub, true).await;
+        // Only the polling state is copied back; the user may have edited the rest meanwhile.
+        // The feed title only replaces the URL placeholder, never a name set by a rename.
+        let titled = had_placeholder && sub.name != sub.url;
+        let _ = update_subscription(&sub.id, |s| {
+            if titled && s.name == s.url {
+                s.name = sub.name.clone();
+            }
+            s.seen = sub.seen.clone();
+            s.last_checked = sub.last_checked;
+            s.last_error = sub.last_error.clone();
+        });
+        match result {
+            Ok(n) => queued += n,
+            Err(e) => {
+                let _ = app.emit(
+                    "download-log",
+                    format!("Falha ao verificar inscrição {}: {}", sub.name, e),
+                );
+            }
+        }
+    }
+    let _ = app.emit("subscriptions-updated", load_subscriptions());
+    queued
+}
+
+// An interval of zero minutes turns polling off
+pub fn spawn_poller(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(TICK_SECS));
+        loop {
+            interval.tick().await;
+            if settings::current(&app).subscription_interval_minutes == 0 {
+                continue;
+            }
+            check_due(&app, false).await;
+        }
+    });
+}
+
+#[tauri::command]
+pub fn list_subscriptions() -> Vec<Subscription> {
+    load_subscriptions()
+}
+
+// Items already in the feed are only marked as seen unless download_existing is set;
+// the first poll happens before saving so the poller never sees an unprimed entry.
+#[tauri::command]
+pub async fn add_subscription(
+    app: tauri::AppHandle,
+    url: String,
+    format_ext: String,
+    format_height: u64,
+    custom_path: Option<String>,
+    options: Option<DownloadOptions>,
+    download_existing: Option<bool>,
+) -> Result<Subscription, AppError> {
+    let yt_dlp_path = tools::path(&app, "yt-dlp")?;
+    if load_subscriptions().iter().any(|s| s.url == url) {
+        return Err(AppError::InvalidState(
+            "Already subscribed to this URL".to_string(),
+        ));
+    }
+    let dest = download::resolve_dest(&app, custom_path)?;
+    let mut sub = Subscription {
+        id: next_subscription_id(),
+        name: url.clone(),
+        url,
+        format_ext,
+        format_height,
+        dest: dest.to_string_lossy().to_string(),
+        options: options.unwrap_or_default(),
+        enabled: true,
+        seen: Vec::new(),
+        last_checked: None,
+        last_error: None,
+    };
+
+    let enqueue = download_existing.unwrap_or(false);
+    poll(&app, &yt_dlp_path, &mut sub, enqueue).await?;
+
+    let _guard = SUBSCRIPTIONS_LOCK.lock().unwrap();
+    let mut subs = load_subscriptions();
+    subs.retain(|s| s.url != sub.url);
+    subs.push(sub.clone());
+    storage::save_json(SUBSCRIPTIONS_FILE, &subs)?;
+    Ok(sub)
+}
+
+#[tauri::command]
+pub fn rename_subscription(id: String, name: String) -> Result<(), AppError> {
+    update_subscription(&id, |s| s.name = name)
+}
+
+#[tauri::command]
+pub fn remove_subscription(id: String) -> Result<(), AppError> {
+    let _guard = SUBSCRIPTIONS_LOCK.lock().unwrap();
+    let mut subs = load_subscriptions();
+    subs.retain(|s| s.id != id);
+    storage::save_json(SUBSCRIPTIONS_FILE, &subs)
+}
+
+#[tauri::command]
+pub fn set_subscription_enabled(id: String, enabled: bool) -> Result<(), AppError> {
+    update_subscription(&id, |s| s.enabled = enabled)
+}
+
+// Checks every subscription right away, ignoring the interval
+#[tauri::command]
+pub async fn check_subscriptions_now(app: tauri::AppHandle) -> Result<usize, AppError> {
+    Ok(check_due(&app, true).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(url: &str, key: &str) -> FeedEntry {
+        FeedEntry {
+            url: url.to_string(),
+            key: key.to_string(),
+        }
+    }
+
+    fn subscription(url: &str) -> Subscription {
+        Subscription {
+            id: "test".to_string(),
+            url: url.to_string(),
+            name: url.to_string(),
+            format_ext: "mp4".to_string(),
+            format_height: 720,
+            dest: String::new(),
+            options: DownloadOptions::default(),
+            enabled: true,
+            seen: Vec::new(),
+            last_checked: None,
+            last_error: None,
+        }
+    }
+
+    #[test]
+    fn new_entries_skips_seen_archived_and_queued() {
+        let seen = vec!["youtube a".to_string()];
+        let archived = HashSet::from(["youtube b".to_string()]);
+        let queued = vec!["https://youtu.be/c".to_string()];
+        let entries = vec![
+            entry("https://youtu.be/e", "youtube e"),
+            entry("https://youtu.be/d", "youtube d"),
+            entry("https://youtu.be/c", "youtube c"),
+            entry("https://youtu.be/b", "youtube b"),
+            entry("https://youtu.be/a", "youtube a"),
+        ];
+        let keys: Vec<String> = new_entries(&seen, &archived, &queued, entries)
+            .into_iter()
+            .map(|e| e.key)
+            .collect();
+        assert_eq!(keys, ["youtube d", "youtube e"]);
+    }
+
+    #[test]
+    fn mark_seen_keeps_the_newest_window() {
+        let mut seen: Vec<String> = (0..MAX_SEEN).map(|i| i.to_string()).collect();
+        mark_seen(&mut seen, ["0".to_string(), "new".to_string()]);
+        assert_eq!(seen.len(), MAX_SEEN);
+        assert_eq!(seen.first().map(String::as_str), Some("1"));
+        assert_eq!(seen.last().map(String::as_str), Some("new"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn fetch_feed_reads_flat_playlist_json() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("yt-dlp");
+        std::fs::write(
+            &script,
+            r#"#!/bin/sh
+cat <<'JSON'
+{"title": "Channel", "entries": [
+  {"ie_key": "Youtube", "id": "new", "url": "https://www.youtube.com/watch?v=new"},
+  {"ie_key": "Youtube", "id": "old", "url": "https://www.youtube.com/watch?v=old"},
+  {"ie_key": "Youtube", "id": ""},
+  {"ie_key": "Youtube", "id": "arch", "webpage_url": "https://www.youtube.com/watch?v=arch"}
+]}
+JSON
+"#,
+        )
+        .unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let mut sub = subscription("https://example.invalid/@channel");
+        sub.seen.push("youtube old".to_string());
+        let feed = fetch_feed(&script, &sub, None, &ProxySettings::default())
+            .await
+            .unwrap();
+        assert_eq!(feed.title.as_deref(), Some("Channel"));
+        assert_eq!(feed.entries.len(), 3);
+
+        let archived = HashSet::from(["youtube arch".to_string()]);
+        let fresh = new_entries(&sub.seen, &archived, &[], feed.entries);
+        assert_eq!(fresh.len(), 1);
+        assert_eq!(fresh[0].url, "https://www.youtube.com/watch?v=new");
+
+        mark_seen(&mut sub.seen, fresh.into_iter().map(|e| e.key));
+        assert_eq!(sub.seen, ["youtube old", "youtube new"]);
+    }
+}
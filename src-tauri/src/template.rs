@@ -0,0 +1,349 @@
+use std::path::{Component, Path};
+
+use crate::error::AppError;
+use crate::settings;
+
+pub const DEFAULT_TEMPLATE: &str = "%(title)s.%(ext)s";
+
+// yt-dlp writes every --download-sections entry to its own file named from the
+// template, so without a section field all sections after the first collide with
+// it and are skipped as "already downloaded"
+pub fn with_section_field(template: &str) -> String {
+    if template.contains("%(section_") {
+        return template.to_string();
+    }
+    match template.strip_suffix(".%(ext)s") {
+        Some(stem) => format!("{} [%(section_start)s].%(ext)s", stem),
+        None => format!("{} [%(section_start)s]", template),
+    }
+}
+
+// Templates are always joined onto the destination folder, so they must stay inside it
+pub fn validate(template: &str) -> Result<(), AppError> {
+    if template.trim().is_empty() {
+        return Err(AppError::invalid(
+            "output_template",
+            "Output template is empty",
+        ));
+    }
+    if !template.contains("%(ext)s") {
+        return Err(AppError::invalid(
+            "output_template",
+            "Output template must contain %(ext)s",
+        ));
+    }
+    let path = Path::new(template);
+    if path.is_absolute() || template.starts_with('/') || template.starts_with('\\') {
+        return Err(AppError::invalid(
+            "output_template",
+            "Output template must be relative to the download folder",
+        ));
+    }
+    if path
+        .components()
+        .any(|c| matches!(c, Component::ParentDir | Component::Prefix(_)))
+    {
+        return Err(AppError::invalid(
+            "output_template",
+            "Output template cannot leave the download folder",
+        ));
+    }
+    Ok(())
+}
+
+fn fold_accent(c: char) -> Option<&'static str> {
+    let folded = match c {
+        'á' | 'à' | 'â' | 'ã' | 'ä' | 'å' => "a",
+        'Á' | 'À' | 'Â' | 'Ã' | 'Ä' | 'Å' => "A",
+        'é' | 'è' | 'ê' | 'ë' => "e",
+        'É' | 'È' | 'Ê' | 'Ë' => "E",
+        'í' | 'ì' | 'î' | 'ï' => "i",
+        'Í' | 'Ì' | 'Î' | 'Ï' => "I",
+        'ó' | 'ò' | 'ô' | 'õ' | 'ö' | 'ø' => "o",
+        'Ó' | 'Ò' | 'Ô' | 'Õ' | 'Ö' | 'Ø' => "O",
+        'ú' | 'ù' | 'û' | 'ü' => "u",
+        'Ú' | 'Ù' | 'Û' | 'Ü' => "U",
+        'ç' => "c",
+        'Ç' => "C",
+        'ñ' => "n",
+        'Ñ' => "N",
+        'ß' => "ss",
+        _ => return None,
+    };
+    Some(folded)
+}
+
+// Mirrors what yt-dlp does to field values so the preview matches the real file name
+pub fn sanitize_component(value: &str, restrict: bool, windows: bool) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        if c.is_control() {
+            continue;
+        }
+        if restrict {
+            if let Some(folded) = fold_accent(c) {
+                out.push_str(folded);
+            } else if c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '(' | ')') {
+                out.push(c);
+            } else {
+                out.push('_');
+            }
+            continue;
+        }
+        match c {
+            '/' => out.push('⧸'),
+            '\\' if windows => out.push('⧹'),
+            '<' if windows => out.push('＜'),
+            '>' if windows => out.push('＞'),
+            ':' if windows => out.push('：'),
+            '"' if windows => out.push('＂'),
+            '|' if windows => out.push('｜'),
+            '?' if windows => out.push('？'),
+            '*' if windows => out.push('＊'),
+            _ => out.push(c),
+        }
+    }
+
+    if restrict {
+        while out.contains("__") {
+            out = out.replace("__", "_");
+        }
+        out = out.trim_matches('_').to_string();
+    }
+    if out.is_empty() {
+        out.push('_');
+    }
+    out
+}
+
+// Windows refuses trailing dots/spaces and legacy device names in any path component
+fn windows_safe_segment(segment: &str) -> String {
+    const RESERVED: [&str; 22] = [
+        "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+        "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+    ];
+    let trimmed = segment.trim_end_matches(['.', ' ']);
+    let stem = trimmed.split('.').next().unwrap_or("").to_uppercase();
+    if RESERVED.contains(&stem.as_str()) {
+        format!("_{}", trimmed)
+    } else {
+        trimmed.to_string()
+    }
+}
+
+fn format_value(value: &serde_json::Value, conversion: &str) -> Option<String> {
+    if value.is_null() {
+        return None;
+    }
+    if conversion.ends_with('d') {
+        let n = value
+            .as_i64()
+            .or_else(|| value.as_f64().map(|f| f as i64))
+            .or_else(|| value.as_str().and_then(|s| s.parse().ok()))?;
+        // Supports zero padding such as %(playlist_index)03d
+        let width: usize = conversion
+            .trim_end_matches('d')
+            .trim_start_matches('0')
+            .parse()
+            .unwrap_or(0);
+        return Some(format!("{:0width$}", n, width = width));
+    }
+    match value {
+        serde_json::Value::String(s) => Some(s.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+// Renders a yt-dlp output template against an info object. Supports %(field)s,
+// %(field)d, %(field)0Nd, %(field|default)s and %%.
+pub fn render(
+    template: &str,
+    info: &serde_json::Value,
+    restrict: bool,
+    windows: bool,
+) -> Result<String, AppError> {
+    let mut out = String::new();
+    let mut rest = template;
+    while let Some(pos) = rest.find('%') {
+        out.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+        if let Some(stripped) = after.strip_prefix('%') {
+            out.push('%');
+            rest = stripped;
+            continue;
+        }
+        let Some(inner) = after.strip_prefix('(') else {
+            out.push('%');
+            rest = after;
+            continue;
+        };
+        let close = inner.find(')').ok_or_else(|| {
+            AppError::invalid("output_template", "Unclosed field in output template")
+        })?;
+        let spec = &inner[..close];
+        let tail = &inner[close + 1..];
+        let conv_len = tail
+            .find(|c: char| c.is_ascii_alphabetic())
+            .ok_or_else(|| {
+                AppError::invalid(
+                    "output_template",
+                    "Missing conversion type in output template",
+                )
+            })?
+            + 1;
+        let conversion = &tail[..conv_len];
+
+        let (field, default) = match spec.split_once('|') {
+            Some((f, d)) => (f, Some(d)),
+            None => (spec, None),
+        };
+        let value = format_value(&info[field], conversion)
+            .or_else(|| default.map(|d| d.to_string()))
+            .unwrap_or_else(|| "NA".to_string());
+        out.push_str(&sanitize_component(&value, restrict, windows));
+        rest = &tail[conv_len..];
+    }
+    out.push_str(rest);
+
+    if windows {
+        out = out
+            .split('/')
+            .map(windows_safe_segment)
+            .collect::<Vec<_>>()
+            .join("/");
+    }
+    Ok(out)
+}
+
+#[tauri::command]
+pub fn preview_output_template(
+    app: tauri::AppHandle,
+    template: Option<String>,
+    info: serde_json::Value,
+    ext: Option<String>,
+    restrict_filenames: Option<bool>,
+    windows_filenames: Option<bool>,
+) -> Result<String, AppError> {
+    let settings = settings::current(&app);
+    let template = template.unwrap_or(settings.output_template);
+    validate(&template)?;
+
+    let mut info = info;
+    if let (Some(ext), Some(obj)) = (ext, info.as_object_mut()) {
+        obj.insert("ext".to_string(), serde_json::Value::String(ext));
+    }
+    render(
+        &template,
+        &info,
+        restrict_filenames.unwrap_or(settings.restrict_filenames),
+        windows_filenames.unwrap_or(settings.windows_filenames),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info() -> serde_json::Value {
+        serde_json::json!({
+            "title": "Ação: part 1/2?",
+            "uploader": "Some Channel",
+            "playlist_index": 7,
+            "duration": 93.5,
+            "ext": "mp4",
+        })
+    }
+
+    #[test]
+    fn renders_fields_padding_and_defaults() {
+        let out = render(
+            "%(uploader)s/%(playlist_index)03d - %(series|No series)s - %(album)s %%.%(ext)s",
+            &info(),
+            false,
+            false,
+        )
+        .unwrap();
+        assert_eq!(out, "Some Channel/007 - No series - NA %.mp4");
+        assert_eq!(
+            render("%(duration)d.%(ext)s", &info(), false, false).unwrap(),
+            "93.mp4"
+        );
+    }
+
+    #[test]
+    fn slashes_in_values_never_create_folders() {
+        assert_eq!(
+            render("%(title)s.%(ext)s", &info(), false, false).unwrap(),
+            "Ação: part 1⧸2?.mp4"
+        );
+        assert_eq!(
+            render("%(title)s.%(ext)s", &info(), false, true).unwrap(),
+            "Ação： part 1⧸2？.mp4"
+        );
+    }
+
+    #[test]
+    fn restrict_folds_accents_and_collapses_underscores() {
+        assert_eq!(
+            render("%(title)s.%(ext)s", &info(), true, false).unwrap(),
+            "Acao_part_1_2.mp4"
+        );
+        assert_eq!(sanitize_component("???", true, false), "_");
+    }
+
+    #[test]
+    fn windows_names_avoid_reserved_words_and_trailing_dots() {
+        let info = serde_json::json!({ "title": "con", "uploader": "dots...", "ext": "mp4" });
+        assert_eq!(
+            render("%(uploader)s/%(title)s.%(ext)s", &info, false, true).unwrap(),
+            "dots/_con.mp4"
+        );
+        assert_eq!(
+            render("%(uploader)s/%(title)s.%(ext)s", &info, false, false).unwrap(),
+            "dots.../con.mp4"
+        );
+    }
+
+    #[test]
+    fn malformed_fields_are_errors() {
+        assert!(render("%(title", &info(), false, false).is_err());
+        assert!(render("%(title)", &info(), false, false).is_err());
+        assert_eq!(
+            render("100% %d.%(ext)s", &info(), false, false).unwrap(),
+            "100% %d.mp4"
+        );
+    }
+
+    #[test]
+    fn section_field_is_added_once() {
+        assert_eq!(
+            with_section_field(DEFAULT_TEMPLATE),
+            "%(title)s [%(section_start)s].%(ext)s"
+        );
+        assert_eq!(
+            with_section_field("%(title)s - %(section_title)s.%(ext)s"),
+            "%(title)s - %(section_title)s.%(ext)s"
+        );
+        assert_eq!(
+            with_section_field("%(title)s.%(ext)s.part"),
+            "%(title)s.%(ext)s.part [%(section_start)s]"
+        );
+    }
+
+    #[test]
+    fn validate_keeps_templates_inside_the_folder() {
+        assert!(validate(DEFAULT_TEMPLATE).is_ok());
+        assert!(validate("%(uploader)s/%(title)s.%(ext)s").is_ok());
+        for template in [
+            "",
+            "  ",
+            "%(title)s",
+            "/tmp/%(title)s.%(ext)s",
+            "\\%(title)s.%(ext)s",
+            "../%(title)s.%(ext)s",
+            "a/../../%(title)s.%(ext)s",
+        ] {
+            assert!(validate(template).is_err(), "{:?}", template);
+        }
+    }
+}
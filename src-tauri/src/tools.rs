@@ -0,0 +1,270 @@
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+use crate::binaries;
+use crate::error::AppError;
+use crate::settings;
+
+const TOOLS: [&str; 2] = ["yt-dlp", "ffmpeg"];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolSource {
+    // The absolute path configured for the tool
+    Custom,
+    // Found by searching PATH, e.g. a distro-packaged ffmpeg
+    System,
+    // Downloaded by the app into its bin folder
+    Managed,
+}
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct ToolSettings {
+    // Absolute paths by tool name, e.g. {"ffmpeg": "/usr/bin/ffmpeg"}
+    pub custom_paths: HashMap<String, String>,
+    // Sources are tried in this order and the first that has the tool wins;
+    // leaving one out means it is never used
+    pub order: Vec<ToolSource>,
+}
+
+impl Default for ToolSettings {
+    fn default() -> Self {
+        Self {
+            custom_paths: HashMap::new(),
+            order: vec![ToolSource::Custom, ToolSource::Managed, ToolSource::System],
+        }
+    }
+}
+
+pub fn validate(tools: &ToolSettings) -> Result<(), AppError> {
+    for (name, path) in &tools.custom_paths {
+        if !TOOLS.contains(&name.as_str()) {
+            return Err(AppError::UnsupportedBinary(name.clone()));
+        }
+        if !path.is_empty() && !Path::new(path).is_absolute() {
+            return Err(AppError::invalid(
+                "tools.custom_paths",
+                format!("Path for {} must be absolute: {}", name, path),
+            ));
+        }
+    }
+    if tools.order.is_empty() {
+        return Err(AppError::invalid(
+            "tools.order",
+            "At least one tool source is required",
+        ));
+    }
+    Ok(())
+}
+
+#[derive(Clone, serde::Serialize)]
+pub struct ResolvedTool {
+    pub path: PathBuf,
+    pub source: ToolSource,
+}
+
+fn is_executable(path: &Path) -> bool {
+    let Ok(metadata) = path.metadata() else {
+        return false;
+    };
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        metadata.is_file() && metadata.permissions().mode() & 0o111 != 0
+    }
+    #[cfg(not(unix))]
+    {
+        metadata.is_file()
+    }
+}
+
+fn exe_name(name: &str) -> String {
+    format!("{}{}", name, std::env::consts::EXE_SUFFIX)
+}
+
+fn find_in_path(search_path: &OsStr, name: &str) -> Option<PathBuf> {
+    std::env::split_paths(search_path)
+        .map(|dir| dir.join(exe_name(name)))
+        .find(|p| is_executable(p))
+}
+
+// Where the System and Managed sources look; taken as arguments so lookups can be
+// exercised against a scratch folder instead of the real PATH and data folder
+struct Locations<'a> {
+    search_path: Option<&'a OsStr>,
+    bin_dir: Option<&'a Path>,
+}
+
+fn lookup(tools: &ToolSettings, name: &str, source: ToolSource, at: &Locations) -> Option<PathBuf> {
+    match source {
+        ToolSource::Custom => tools
+            .custom_paths
+            .get(name)
+            .filter(|p| !p.is_empty())
+            .map(PathBuf::from)
+            .filter(|p| is_executable(p)),
+        ToolSource::System => find_in_path(at.search_path?, name),
+        ToolSource::Managed => at
+            .bin_dir
+            .map(|dir| dir.join(exe_name(name)))
+            .filter(|p| p.exists()),
+    }
+}
+
+fn resolve_with(
+    tools: &ToolSettings,
+    name: &str,
+    at: &Locations,
+) -> Result<ResolvedTool, AppError> {
+    if !TOOLS.contains(&name) {
+        return Err(AppError::UnsupportedBinary(name.to_string()));
+    }
+    tools
+        .order
+        .iter()
+        .find_map(|&source| {
+            lookup(tools, name, source, at).map(|path| ResolvedTool { path, source })
+        })
+        .ok_or_else(|| AppError::NotInstalled(name.to_string()))
+}
+
+// The executable to run for `name`, or NotInstalled when no configured source has it
+pub fn resolve(app: &tauri::AppHandle, name: &str) -> Result<ResolvedTool, AppError> {
+    let search_path = std::env::var_os("PATH");
+    let bin_dir = binaries::get_bin_dir().ok();
+    let at = Locations {
+        search_path: search_path.as_deref(),
+        bin_dir: bin_dir.as_deref(),
+    };
+    resolve_with(&settings::current(app).tools, name, &at)
+}
+
+pub fn path(app: &tauri::AppHandle, name: &str) -> Result<PathBuf, AppError> {
+    resolve(app, name).map(|t| t.path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Lays out one yt-dlp per source: a custom path, a PATH entry and a managed bin folder
+    struct Sandbox {
+        _dir: tempfile::TempDir,
+        custom: PathBuf,
+        system_dir: PathBuf,
+        bin_dir: PathBuf,
+    }
+
+    fn touch(path: &Path, executable: bool) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, b"").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = if executable { 0o755 } else { 0o644 };
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode)).unwrap();
+        }
+        #[cfg(not(unix))]
+        let _ = executable;
+    }
+
+    fn sandbox() -> Sandbox {
+        let dir = tempfile::tempdir().unwrap();
+        let custom = dir.path().join("custom").join(exe_name("yt-dlp"));
+        let system_dir = dir.path().join("system");
+        let bin_dir = dir.path().join("bin");
+        touch(&custom, true);
+        touch(&system_dir.join(exe_name("yt-dlp")), true);
+        touch(&bin_dir.join(exe_name("yt-dlp")), true);
+        Sandbox {
+            _dir: dir,
+            custom,
+            system_dir,
+            bin_dir,
+        }
+    }
+
+    fn settings(custom: &Path, order: Vec<ToolSource>) -> ToolSettings {
+        ToolSettings {
+            custom_paths: HashMap::from([(
+                "yt-dlp".to_string(),
+                custom.to_string_lossy().into_owned(),
+            )]),
+            order,
+        }
+    }
+
+    fn resolve_in(sb: &Sandbox, tools: &ToolSettings) -> Result<ResolvedTool, AppError> {
+        // A missing folder first, like a real PATH often has
+        let search_path =
+            std::env::join_paths([Path::new("/nonexistent"), sb.system_dir.as_path()]).unwrap();
+        let at = Locations {
+            search_path: Some(&search_path),
+            bin_dir: Some(&sb.bin_dir),
+        };
+        resolve_with(tools, "yt-dlp", &at)
+    }
+
+    #[test]
+    fn the_first_source_in_order_wins() {
+        use ToolSource::*;
+        let sb = sandbox();
+        let system = sb.system_dir.join(exe_name("yt-dlp"));
+        let managed = sb.bin_dir.join(exe_name("yt-dlp"));
+        for (order, path, source) in [
+            (vec![Custom, System, Managed], &sb.custom, Custom),
+            (vec![System, Managed, Custom], &system, System),
+            (vec![Managed, Custom, System], &managed, Managed),
+        ] {
+            let tool = resolve_in(&sb, &settings(&sb.custom, order)).unwrap();
+            assert_eq!(tool.source, source);
+            assert_eq!(&tool.path, path);
+        }
+    }
+
+    #[test]
+    fn omitted_sources_are_never_used() {
+        let sb = sandbox();
+        std::fs::remove_file(&sb.custom).unwrap();
+        let tools = settings(&sb.custom, vec![ToolSource::Custom]);
+        assert!(matches!(
+            resolve_in(&sb, &tools),
+            Err(AppError::NotInstalled(_))
+        ));
+
+        let tools = settings(&sb.custom, vec![ToolSource::Custom, ToolSource::Managed]);
+        assert_eq!(resolve_in(&sb, &tools).unwrap().source, ToolSource::Managed);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn non_executable_custom_paths_are_skipped() {
+        let sb = sandbox();
+        touch(&sb.custom, false);
+        let tools = settings(&sb.custom, vec![ToolSource::Custom, ToolSource::System]);
+        assert_eq!(resolve_in(&sb, &tools).unwrap().source, ToolSource::System);
+    }
+
+    #[test]
+    fn validate_rejects_bad_settings() {
+        let sb = sandbox();
+        assert!(validate(&settings(&sb.custom, vec![ToolSource::Custom])).is_ok());
+        assert!(validate(&settings(&sb.custom, vec![])).is_err());
+        assert!(validate(&settings(
+            Path::new("relative/yt-dlp"),
+            vec![ToolSource::Custom]
+        ))
+        .is_err());
+
+        let mut unknown = settings(&sb.custom, vec![ToolSource::Custom]);
+        unknown
+            .custom_paths
+            .insert("aria2c".to_string(), String::new());
+        assert!(matches!(
+            validate(&unknown),
+            Err(AppError::UnsupportedBinary(_))
+        ));
+    }
+}
@@ -0,0 +1,299 @@
+use std::time::Duration;
+
+use tauri::Emitter;
+
+use crate::binaries;
+use crate::error::AppError;
+use crate::net;
+use crate::queue;
+use crate::settings;
+use crate::tools;
+use crate::tools::ToolSource;
+
+const MANAGED: [&str; 2] = ["yt-dlp", "ffmpeg"];
+// The queue waits for the startup check when auto_update is on, so it must not hang
+const CHECK_TIMEOUT: Duration = Duration::from_secs(20);
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct UpdateSettings {
+    // Each endpoint may answer with GitHub-style release JSON ({"tag_name": ...}),
+    // a text file with a "version: x.y.z" line, or just the bare version
+    pub yt_dlp_endpoint: String,
+    pub ffmpeg_endpoint: String,
+    // Install newer versions when the app starts
+    pub auto_update: bool,
+}
+
+impl Default for UpdateSettings {
+    fn default() -> Self {
+        let ffmpeg_endpoint = if cfg!(target_os = "windows") {
+            "https://www.gyan.dev/ffmpeg/builds/release-version"
+        } else {
+            "https://johnvansickle.com/ffmpeg/release-readme.txt"
+        };
+        Self {
+            yt_dlp_endpoint: "https://api.github.com/repos/yt-dlp/yt-dlp/releases/latest"
+                .to_string(),
+            ffmpeg_endpoint: ffmpeg_endpoint.to_string(),
+            auto_update: false,
+        }
+    }
+}
+
+impl UpdateSettings {
+    fn endpoint(&self, name: &str) -> &str {
+        if name == "yt-dlp" {
+            &self.yt_dlp_endpoint
+        } else {
+            &self.ffmpeg_endpoint
+        }
+    }
+}
+
+pub fn validate(updates: &UpdateSettings) -> Result<(), AppError> {
+    for (field, url) in [
+        ("updates.yt_dlp_endpoint", &updates.yt_dlp_endpoint),
+        ("updates.ffmpeg_endpoint", &updates.ffmpeg_endpoint),
+    ] {
+        let parsed = reqwest::Url::parse(url)
+            .map_err(|e| AppError::invalid(field, format!("Invalid endpoint '{}': {}", url, e)))?;
+        if parsed.scheme() != "http" && parsed.scheme() != "https" {
+            return Err(AppError::invalid(
+                field,
+                format!("Endpoint must be http or https: {}", url),
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[derive(Clone, serde::Serialize)]
+pub struct BinaryUpdate {
+    pub name: String,
+    // None when the binary is not installed
+    pub installed: Option<String>,
+    pub source: Option<ToolSource>,
+    pub latest: Option<String>,
+    pub update_available: bool,
+    pub error: Option<AppError>,
+}
+
+fn parse_latest(body: &str) -> Option<String> {
+    // A bare "7.1" is valid JSON too, so only objects count as release metadata
+    if let Ok(json @ serde_json::Value::Object(_)) = serde_json::from_str(body) {
+        return json["tag_name"]
+            .as_str()
+            .or_else(|| json["version"].as_str())
+            .map(|v| v.trim_start_matches('v').to_string());
+    }
+    let version = body
+        .lines()
+        .find_map(|l| l.trim().strip_prefix("version:"))
+        .unwrap_or(body)
+        .trim();
+    (!version.is_empty() && !version.contains(char::is_whitespace)).then(|| version.to_string())
+}
+
+// "2024.08.06" -> [2024, 8, 6] and "n7.0" -> [7, 0]; suffixes such as "-static" or "-essentials_build" are ignored
+fn numeric_parts(version: &str) -> Vec<u64> {
+    version
+        .split(|c: char| !c.is_ascii_digit())
+        // Leading letters, as in ffmpeg's "n7.0", leave empty segments before the number
+        .skip_while(|p| p.is_empty())
+        .take_while(|p| !p.is_empty())
+        .filter_map(|p| p.parse().ok())
+        .collect()
+}
+
+fn is_newer(latest: &str, installed: &str) -> bool {
+    let latest = numeric_parts(latest);
+    !latest.is_empty() && latest > numeric_parts(installed)
+}
+
+async fn fetch_latest(client: &reqwest::Client, endpoint: &str) -> Result<String, AppError> {
+    let body = client
+        .get(endpoint)
+        .timeout(CHECK_TIMEOUT)
+        // The GitHub API rejects requests without a User-Agent
+        .header(
+            reqwest::header::USER_AGENT,
+            concat!("mevideo/", env!("CARGO_PKG_VERSION")),
+        )
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    parse_latest(&body).ok_or_else(|| AppError::Parse(format!("No version found at {}", endpoint)))
+}
+
+async fn check_one(app: &tauri::AppHandle, name: &str) -> BinaryUpdate {
+    let endpoint = settings::current(app).updates.endpoint(name).to_string();
+    let tool = tools::resolve(app, name).ok();
+    let installed = match &tool {
+        Some(tool) => binaries::version(name, &tool.path).await.ok(),
+        None => None,
+    };
+    let (latest, error) = match fetch_latest(&net::client(app), &endpoint).await {
+        Ok(v) => (Some(v), None),
+        Err(e) => (None, Some(e)),
+    };
+    let update_available = match (&latest, &installed) {
+        (Some(latest), Some(installed)) => is_newer(latest, installed),
+        _ => false,
+    };
+    BinaryUpdate {
+        name: name.to_string(),
+        installed,
+        source: tool.map(|t| t.source),
+        latest,
+        update_available,
+        error,
+    }
+}
+
+async fn check_all(app: &tauri::AppHandle) -> Vec<BinaryUpdate> {
+    let mut updates = Vec::new();
+    for name in MANAGED {
+        updates.push(check_one(app, name).await);
+    }
+    updates
+}
+
+#[tauri::command]
+pub async fn check_binary_updates(app: tauri::AppHandle) -> Vec<BinaryUpdate> {
+    check_all(&app).await
+}
+
+async fn install(app: &tauri::AppHandle, name: &str) -> Result<(), AppError> {
+    let client = net::client(app);
+    if name == "yt-dlp" {
+        binaries::download_yt_dlp(app, &client).await
+    } else {
+        binaries::download_ffmpeg(app, &client).await
+    }
+}
+
+// Checks once at launch and reports the result as `binary-updates`. Until this calls
+// queue::start no download runs, so with auto_update on the out-of-date binaries in
+// the managed folder are replaced first; Windows cannot replace an executable that is
+// in use. Custom and system tools are left to whoever installed them.
+pub fn spawn_startup_check(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        if !settings::current(&app).updates.auto_update {
+            queue::start(&app);
+            let _ = app.emit("binary-updates", check_all(&app).await);
+            return;
+        }
+
+        let updates = check_all(&app).await;
+        let _ = app.emit("binary-updates", updates.clone());
+        for update in updates
+            .iter()
+            .filter(|u| u.update_available && u.source == Some(ToolSource::Managed))
+        {
+            let latest = update.latest.as_deref().unwrap_or_default();
+            let _ = app.emit(
+                "download-log",
+                format!("🔄 Atualizando {} para {}...", update.name, latest),
+            );
+            let message = match install(&app, &update.name).await {
+                Ok(()) => format!("✅ {} atualizado para {}", update.name, latest),
+                Err(e) => format!("Falha ao atualizar {}: {}", update.name, e),
+            };
+            let _ = app.emit("download-log", message);
+        }
+        queue::start(&app);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    // Answers a single request with `body` and returns the URL to fetch it from
+    async fn serve(body: &'static str) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                let n = socket.read(&mut buf).await.unwrap();
+                if n == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buf[..n]);
+            }
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+        });
+        format!("http://{}/latest", addr)
+    }
+
+    #[test]
+    fn parse_latest_reads_each_body_shape() {
+        assert_eq!(
+            parse_latest(r#"{"tag_name": "2024.08.06", "name": "yt-dlp 2024.08.06"}"#).as_deref(),
+            Some("2024.08.06")
+        );
+        assert_eq!(
+            parse_latest(r#"{"version": "v7.1"}"#).as_deref(),
+            Some("7.1")
+        );
+        assert_eq!(
+            parse_latest("build: ffmpeg-git\nversion: 7.0.2\n").as_deref(),
+            Some("7.0.2")
+        );
+        assert_eq!(parse_latest("7.1\n").as_deref(), Some("7.1"));
+        assert_eq!(parse_latest(r#"{"message": "Not Found"}"#), None);
+        assert_eq!(parse_latest("<html>rate limited</html> "), None);
+        assert_eq!(parse_latest(""), None);
+    }
+
+    #[test]
+    fn numeric_parts_ignores_prefixes_and_suffixes() {
+        assert_eq!(numeric_parts("2024.08.06"), [2024, 8, 6]);
+        assert_eq!(numeric_parts("n7.0"), [7, 0]);
+        assert_eq!(numeric_parts("7.0.2-static"), [7, 0, 2]);
+        assert_eq!(numeric_parts("7.1-essentials_build-www.gyan.dev"), [7, 1]);
+        assert!(numeric_parts("unknown").is_empty());
+    }
+
+    #[test]
+    fn is_newer_compares_numerically() {
+        assert!(is_newer("2024.10.22", "2024.08.06"));
+        assert!(is_newer("2024.08.10", "2024.08.6"));
+        assert!(is_newer("7.1", "n7.0"));
+        assert!(is_newer("7.0.1", "7.0"));
+        assert!(!is_newer("7.0", "7.0-static"));
+        assert!(!is_newer("2024.08.06", "2024.10.22"));
+        assert!(!is_newer("latest", "7.0"));
+    }
+
+    #[tokio::test]
+    async fn fetch_latest_handles_each_endpoint_shape() {
+        let client = reqwest::Client::new();
+        for (body, expected) in [
+            (r#"{"tag_name": "2024.08.06"}"#, "2024.08.06"),
+            ("build: release\nversion: 7.0.2\n", "7.0.2"),
+            ("7.1", "7.1"),
+        ] {
+            let url = serve(body).await;
+            assert_eq!(fetch_latest(&client, &url).await.unwrap(), expected);
+        }
+
+        let url = serve("no version here").await;
+        assert!(matches!(
+            fetch_latest(&client, &url).await,
+            Err(AppError::Parse(_))
+        ));
+    }
+}
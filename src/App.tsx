@@ -22,6 +22,64 @@ interface FormatOption {
   filesize?: number;
 }
 
+// Rust commands reject with { code, message_key, message, details, cause }
+interface AppError {
+  code: string;
+  message_key: string;
+  message: string;
+  details: unknown;
+  cause: string | null;
+}
+
+// Translated from message_key when `t` has it, with {placeholders} filled from details;
+// otherwise the English message from the backend
+function errorMessage(e: unknown, t?: Record<string, string>): string {
+  if (typeof e !== "object" || e === null || !("message" in e)) {
+    return String(e);
+  }
+  const err = e as AppError;
+  const template = t?.[err.message_key];
+  if (!template) {
+    return err.message;
+  }
+  const details = (typeof err.details === "object" && err.details !== null ? err.details : {}) as Record<string, unknown>;
+  const text = template.replace(/\{(\w+)\}/g, (m, key) => (key in details ? String(details[key]) : m));
+  // The yt-dlp ERROR line is more useful than the English cause it was folded into
+  const detail = err.code === "download_failed"
+    ? (details.stderr as string[] | undefined)?.filter(l => l.startsWith("ERROR:")).pop()?.slice(6).trim()
+    : err.cause;
+  return detail ? `${text}: ${detail}` : text;
+}
+
+// Where the active yt-dlp/ffmpeg comes from: a configured path, PATH or the app's bin folder
+type ToolSource = "custom" | "system" | "managed";
+
+const TOOL_SOURCE_LABELS: Record<ToolSource, string> = {
+  custom: "caminho personalizado",
+  system: "sistema",
+  managed: "gerenciado",
+};
+
+// Emitted by the Rust side while yt-dlp/ffmpeg are being installed
+interface InstallProgress {
+  name: string;
+  phase: "download" | "verify" | "extract" | "chmod";
+  received: number;
+  total: number | null;
+  speed: number | null;
+}
+
+function describeInstall(p: InstallProgress): string {
+  if (p.phase !== "download") {
+    const labels = { verify: "verificando", extract: "extraindo", chmod: "ajustando permissões" };
+    return `${p.name}: ${labels[p.phase]}...`;
+  }
+  const mb = (bytes: number) => (bytes / 1024 / 1024).toFixed(1);
+  const size = p.total ? `${mb(p.received)}/${mb(p.total)} MB` : `${mb(p.received)} MB`;
+  const speed = p.speed ? ` (${mb(p.speed)} MB/s)` : "";
+  return `${p.name}: ${size}${speed}`;
+}
+
 interface UpdateAsset {
   os: 'windows' | 'mac' | 'linux';
   ext: string;
@@ -160,13 +218,23 @@ function Onboarding({ onFinish, lang, setLang }: {
   lang: Language;
   setLang: (l: Language) => void;
 }) {
+  const t = i18n[lang] as Record<string, string>;
   const [step, setStep] = useState(0);
   const [installing, setInstalling] = useState<string | null>(null);
   const [installed, setInstalled] = useState<Record<string, boolean>>({});
   const [installError, setInstallError] = useState<string | null>(null);
+  const [installProgress, setInstallProgress] = useState<InstallProgress | null>(null);
 
   const TOTAL_STEPS = 4;
 
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    import("@tauri-apps/api/event").then(({ listen }) =>
+      listen<InstallProgress>("binary-progress", (event) => setInstallProgress(event.payload))
+    ).then(u => { unlisten = u; }).catch(() => {});
+    return () => { unlisten && unlisten(); };
+  }, []);
+
   const installDep = async (name: string) => {
     setInstalling(name);
     setInstallError(null);
@@ -174,7 +242,7 @@ function Onboarding({ onFinish, lang, setLang }: {
       await invoke("download_binary", { name, lang });
       setInstalled(prev => ({ ...prev, [name]: true }));
     } catch (e) {
-      setInstallError(`Falha ao instalar ${name}: ${e}`);
+      setInstallError(`Falha ao instalar ${name}: ${errorMessage(e, t)}`);
     } finally {
       setInstalling(null);
     }
@@ -191,7 +259,7 @@ function Onboarding({ onFinish, lang, setLang }: {
       await invoke("download_binary", { name: "ffmpeg", lang });
       setInstalled(prev => ({ ...prev, "ffmpeg": true }));
     } catch (e) {
-      setInstallError(`Falha na instalação: ${e}`);
+      setInstallError(`Falha na instalação: ${errorMessage(e, t)}`);
     } finally {
       setInstalling(null);
     }
@@ -263,7 +331,10 @@ function Onboarding({ onFinish, lang, setLang }: {
               <div style={{ fontSize: "0.7rem", opacity: 0.4 }}>{desc}</div>
             </div>
             {installing === name ? (
-              <div className="spinner" />
+              <>
+                {installProgress?.name === name && <span style={{ fontSize: "0.7rem", opacity: 0.5 }}>{describeInstall(installProgress)}</span>}
+                <div className="spinner" />
+              </>
             ) : installed[name] ? (
               <span style={{ color: "#4ade80", fontSize: "0.8rem" }}>✓</span>
             ) : (
@@ -387,11 +458,13 @@ function App() {
     } catch { return []; }
   });
   const scrollRef = useRef<HTMLDivElement>(null);
-  const [binaries, setBinaries] = useState<{ name: string; exists: boolean; version?: string }[]>([
+  const [binaries, setBinaries] = useState<{ name: string; exists: boolean; version?: string; source?: ToolSource }[]>([
     { name: "yt-dlp", exists: false },
     { name: "ffmpeg", exists: false },
   ]);
   const [updateUrl, setUpdateUrl] = useState<string | null>(null);
+  // Newer yt-dlp/ffmpeg releases, by binary name
+  const [binaryUpdates, setBinaryUpdates] = useState<Record<string, string>>({});
   const [updateAssets, setUpdateAssets] = useState<UpdateAsset[]>([]);
 
   useEffect(() => {
@@ -435,17 +508,28 @@ function App() {
         const { listen } = await import("@tauri-apps/api/event");
         const unlistenDown = await listen<string>("download-log", (event) => {
           const payload = event.payload;
-          const pct = payload.match(/\[download\]\s+(\d+(?:\.\d+)?)%/);
-          if (pct) {
-            setDownloadProgress(parseFloat(pct[1]));
-          }
           setLogs(prev => [...prev.slice(-49), `[${new Date().toLocaleTimeString()}] ${payload}`]);
         });
+        const unlistenProgress = await listen<{ percent: number | null }>("download-progress", (event) => {
+          if (event.payload.percent != null) {
+            setDownloadProgress(event.payload.percent);
+          }
+        });
+        // yt-dlp stderr; only warnings and errors are worth showing in the log
+        const unlistenMessage = await listen<{ severity: "info" | "warning" | "error"; message: string }>("download-message", (event) => {
+          const { severity, message } = event.payload;
+          if (severity === "info") return;
+          const icon = severity === "error" ? "❌" : "⚠️";
+          setLogs(prev => [...prev.slice(-49), `[${new Date().toLocaleTimeString()}] ${icon} ${message}`]);
+        });
+        const unlistenInstall = await listen<InstallProgress>("binary-progress", (event) => {
+          setStatus(describeInstall(event.payload));
+        });
         const unlistenComp = await listen<string>("compress-log", (event) => {
           const payload = event.payload;
           setLogs(prev => [...prev.slice(-49), `[${new Date().toLocaleTimeString()}] ${payload}`]);
         });
-        return () => { unlistenDown(); unlistenComp(); };
+        return () => { unlistenDown(); unlistenProgress(); unlistenMessage(); unlistenInstall(); unlistenComp(); };
       } catch (e) {
         console.error("Failed to setup log listener:", e);
         return () => {};
@@ -465,18 +549,34 @@ function App() {
     }
   }, [logs]);
 
+  const checkBinaryUpdates = async () => {
+    try {
+      const updates = await invoke("check_binary_updates") as { name: string; latest: string | null; update_available: boolean }[];
+      const available: Record<string, string> = {};
+      for (const u of updates) {
+        if (u.update_available && u.latest) available[u.name] = u.latest;
+      }
+      setBinaryUpdates(available);
+    } catch {}
+  };
+
+  useEffect(() => {
+    checkBinaryUpdates();
+  }, []);
+
   const checkBinaries = async () => {
     const updated = await Promise.all(
       binaries.map(async (bin) => {
-        const exists = await invoke("check_binary", { name: bin.name }) as boolean;
+        const { exists, source } = await invoke("check_binary", { name: bin.name }) as { exists: boolean; source: ToolSource | null };
         let version = undefined;
         if (exists) {
-          try { version = await invoke("get_binary_version", { name: bin.name }) as string; } catch {}
+          try { version = (await invoke("get_binary_version", { name: bin.name }) as { version: string }).version; } catch {}
         }
         return {
           ...bin,
           exists,
           version,
+          source: source ?? undefined,
         };
       })
     );
@@ -490,8 +590,9 @@ function App() {
       await invoke("download_binary", { name, lang });
       setStatus(`${name} ${t.finished}.`);
       checkBinaries();
+      checkBinaryUpdates();
     } catch (e) {
-      setStatus(`${t.error}: ${e}`);
+      setStatus(`${t.error}: ${errorMessage(e, t)}`);
     } finally {
       setIsLoading(null);
     }
@@ -511,7 +612,7 @@ function App() {
       setSelectedFormat((bestMp4 ?? formats[0])?.format_id || "");
       setStatus(t.ready);
     } catch (e) {
-      setStatus(`${t.error}: ${e}`);
+      setStatus(`${t.error}: ${errorMessage(e, t)}`);
     } finally {
       setIsLoading(null);
     }
@@ -531,13 +632,15 @@ function App() {
     setStatus(t.processing);
     try {
       const selFmt = (videoInfo?.formats as FormatOption[])?.find(f => f.format_id === selectedFormat);
-      const savedPath = await invoke<string>("download_video", { 
+      const result = await invoke<{ path: string; file: string | null; files: { path: string; clipped_duration: number | null }[]; skipped: boolean }>("download_video", { 
         url: videoUrl, 
         formatId: selectedFormat,
         formatExt: selFmt?.ext || "mp4",
         formatHeight: selFmt?.height ?? 0,
-        customPath: downloadPath 
+        customPath: downloadPath,
+        options: { archive_id: videoInfo?.archive_id ?? null }
       });
+      const savedPath = result.path;
       // Save to history
       const entry: DownloadEntry = {
         id: Date.now().toString(),
@@ -564,7 +667,7 @@ function App() {
       invoke("open_path", { path: savedPath }).catch(console.error);
     } catch (e) {
       console.error(e);
-      setStatus(`${t.error}: ${e}`);
+      setStatus(`${t.error}: ${errorMessage(e, t)}`);
     } finally {
       setIsLoading(null);
       // Reset progress after a short delay so user sees 100%
@@ -659,7 +762,7 @@ function App() {
       invoke("open_path", { path: savedPath }).catch(console.error);
     } catch (e) {
       console.error(e);
-      setStatus(`${t.error}: ${e}`);
+      setStatus(`${t.error}: ${errorMessage(e, t)}`);
     } finally {
       setIsLoading(null);
     }
@@ -1069,6 +1172,8 @@ function App() {
                     <div style={{ display: "flex", alignItems: "baseline", gap: "6px" }}>
                       <span style={{ fontWeight: "600", fontSize: "0.9rem" }}>{bin.name}</span>
                       {bin.version && <span style={{ fontSize: "0.65rem", opacity: 0.5, fontFamily: "monospace" }}>v{bin.version}</span>}
+                      {bin.source && bin.source !== "managed" && <span style={{ fontSize: "0.6rem", opacity: 0.4 }}>({TOOL_SOURCE_LABELS[bin.source]})</span>}
+                      {binaryUpdates[bin.name] && <span style={{ fontSize: "0.65rem", color: "#facc15", fontFamily: "monospace" }}>→ v{binaryUpdates[bin.name]}</span>}
                     </div>
                     <button onClick={() => openRepoUrl(bin.name)} style={{ border: "none", background: "none", padding: 0, opacity: 0.3, cursor: "pointer" }} title={t.repo_tooltip}>
                       <svg width="12" height="12" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2"><path d="M18 13v6a2 2 0 0 1-2 2H5a2 2 0 0 1-2-2V8a2 2 0 0 1 2-2h6"></path><polyline points="15 3 21 3 21 9"></polyline><line x1="10" y1="14" x2="21" y2="3"></line></svg>
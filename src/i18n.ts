@@ -48,7 +48,30 @@ export const i18n = {
     res_original: "Original",
     compress_video: "Comprimir Vídeo",
     compression_logs: "Logs de Compressão",
-    history_tab: "Histórico"
+    history_tab: "Histórico",
+    "errors.not_installed": "{name} não está instalado",
+    "errors.unsupported_binary": "Binário não suportado: {name}",
+    "errors.not_found": "Não encontrado: {id}",
+    "errors.invalid_input": "Valor inválido ({field}): {reason}",
+    "errors.invalid_state": "Ação não permitida no estado atual",
+    "errors.data_dir_unavailable": "Pasta de dados indisponível",
+    "errors.io": "Erro de arquivo",
+    "errors.network": "Erro de rede",
+    "errors.process_failed": "{program} falhou",
+    "errors.parse": "Resposta inesperada",
+    "errors.checksum_mismatch": "Checksum do {name} não confere, instalação recusada",
+    "errors.cancelled": "Download cancelado",
+    "errors.internal": "Erro interno",
+    "errors.download.network": "Falha de rede",
+    "errors.download.forbidden": "Acesso negado pelo servidor (HTTP 403)",
+    "errors.download.rate_limited": "Muitas requisições (HTTP 429)",
+    "errors.download.geo_blocked": "Vídeo bloqueado na sua região",
+    "errors.download.unavailable": "Vídeo privado ou removido",
+    "errors.download.login_required": "Login necessário, importe os cookies do site",
+    "errors.download.extractor_outdated": "Extrator desatualizado, atualize o yt-dlp",
+    "errors.download.merge_failed": "Falha ao juntar os arquivos com o ffmpeg",
+    "errors.download.disk_full": "Sem espaço em disco",
+    "errors.download.unknown": "Processo do yt-dlp falhou"
   },
   en: {
     title: "Mevideo",
@@ -97,7 +120,30 @@ export const i18n = {
     res_original: "Original",
     compress_video: "Compress Video",
     compression_logs: "Compression Logs",
-    history_tab: "History"
+    history_tab: "History",
+    "errors.not_installed": "{name} is not installed",
+    "errors.unsupported_binary": "Binary not supported: {name}",
+    "errors.not_found": "Not found: {id}",
+    "errors.invalid_input": "Invalid value ({field}): {reason}",
+    "errors.invalid_state": "Action not allowed in the current state",
+    "errors.data_dir_unavailable": "Data folder unavailable",
+    "errors.io": "File error",
+    "errors.network": "Network error",
+    "errors.process_failed": "{program} failed",
+    "errors.parse": "Unexpected response",
+    "errors.checksum_mismatch": "Checksum mismatch for {name}, refusing to install",
+    "errors.cancelled": "Download cancelled",
+    "errors.internal": "Internal error",
+    "errors.download.network": "Network failure",
+    "errors.download.forbidden": "Access denied by the server (HTTP 403)",
+    "errors.download.rate_limited": "Too many requests (HTTP 429)",
+    "errors.download.geo_blocked": "Video blocked in your region",
+    "errors.download.unavailable": "Video is private or was removed",
+    "errors.download.login_required": "Login required, import the site's cookies",
+    "errors.download.extractor_outdated": "Extractor out of date, update yt-dlp",
+    "errors.download.merge_failed": "ffmpeg failed to merge the files",
+    "errors.download.disk_full": "Out of disk space",
+    "errors.download.unknown": "yt-dlp process failed"
   },
   es: {
     title: "Mevideo",
@@ -146,7 +192,30 @@ export const i18n = {
     res_original: "Original",
     compress_video: "Comprimir Video",
     compression_logs: "Registros de Compresión",
-    history_tab: "Historial"
+    history_tab: "Historial",
+    "errors.not_installed": "{name} no está instalado",
+    "errors.unsupported_binary": "Binario no soportado: {name}",
+    "errors.not_found": "No encontrado: {id}",
+    "errors.invalid_input": "Valor no válido ({field}): {reason}",
+    "errors.invalid_state": "Acción no permitida en el estado actual",
+    "errors.data_dir_unavailable": "Carpeta de datos no disponible",
+    "errors.io": "Error de archivo",
+    "errors.network": "Error de red",
+    "errors.process_failed": "{program} falló",
+    "errors.parse": "Respuesta inesperada",
+    "errors.checksum_mismatch": "El checksum de {name} no coincide, instalación rechazada",
+    "errors.cancelled": "Descarga cancelada",
+    "errors.internal": "Error interno",
+    "errors.download.network": "Fallo de red",
+    "errors.download.forbidden": "Acceso denegado por el servidor (HTTP 403)",
+    "errors.download.rate_limited": "Demasiadas solicitudes (HTTP 429)",
+    "errors.download.geo_blocked": "Video bloqueado en tu región",
+    "errors.download.unavailable": "Video privado o eliminado",
+    "errors.download.login_required": "Inicio de sesión necesario, importa las cookies del sitio",
+    "errors.download.extractor_outdated": "Extractor desactualizado, actualiza yt-dlp",
+    "errors.download.merge_failed": "Fallo al unir los archivos con ffmpeg",
+    "errors.download.disk_full": "Sin espacio en disco",
+    "errors.download.unknown": "El proceso de yt-dlp falló"
   }
 };
 